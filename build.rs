@@ -0,0 +1,37 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// With the 'embed-inputs' feature, generates a table of every non-empty `inputs/day*.txt`, and of
+/// samples.toml, that `include_str!`s it, so the binary runs without the inputs next to it.
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_none() {
+        return;
+    }
+    let dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("inputs");
+    // Cargo reruns the script on every build if a watched path is missing, so a missing directory is not watched.
+    let entries = if dir.is_dir() {
+        println!("cargo:rerun-if-changed=inputs");
+        fs::read_dir(&dir).unwrap().flatten().collect()
+    } else {
+        println!("cargo:warning=embed-inputs is on, but there is no inputs/ directory to embed (touch build.rs once it exists)");
+        vec![]
+    };
+    let mut files = entries.into_iter()
+        .filter(|entry| entry.metadata().is_ok_and(|m| m.is_file() && m.len() > 0))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.starts_with("day") && name.ends_with(".txt"))
+        .collect::<Vec<String>>();
+    files.sort();
+    let mut entries = files.iter()
+        .map(|name| format!("    ({:?}, include_str!({:?})),\n", format!("inputs/{}", name), dir.join(name)))
+        .collect::<String>();
+    let samples = dir.with_file_name("samples.toml");
+    if samples.is_file() {
+        println!("cargo:rerun-if-changed=samples.toml");
+        entries.push_str(&format!("    (\"samples.toml\", include_str!({:?})),\n", samples));
+    }
+    let table = format!("pub static INPUTS: &[(&str, &str)] = &[\n{}];\n", entries);
+    fs::write(Path::new(&env::var("OUT_DIR").unwrap()).join("embedded_inputs.rs"), table).unwrap();
+}
@@ -0,0 +1,63 @@
+﻿use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::solution::Part;
+use crate::utils;
+use crate::error::Error;
+
+pub static ANSWERS: &str = "answers.toml";
+
+static HEADER: &str = "# Known answers for the actual inputs per day and part, checked by `aoc verify`.
+# Missing entries can be filled in from the current results with `aoc verify --record`.
+# The answers for samples are in samples.toml.
+
+";
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+struct Entry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    actual: Option<String>
+}
+
+/// Known answers for the actual inputs, stored as `[dayNN.partN]` tables with an `actual` key.
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(transparent)]
+pub struct Answers {
+    days: BTreeMap<String, BTreeMap<String, Entry>>
+}
+
+fn day_key(day: u8) -> String {
+    format!("day{:02}", day)
+}
+
+fn part_key(part: Part) -> String {
+    format!("part{}", part)
+}
+
+impl Answers {
+    pub fn load(path: &str) -> Result<Answers, Error> {
+        if !Path::new(path).exists() {
+            return Ok(Answers::default());
+        }
+        toml::from_str(&utils::read_file(path)?)
+            .map_err(|e| Error::store(format!("Failed to parse '{}'", path), e))
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        let content = toml::to_string(self)
+            .map_err(|e| Error::store("Failed to serialize answers", e))?;
+        fs::write(path, format!("{}{}", HEADER, content))?;
+        Ok(())
+    }
+
+    pub fn expected(&self, day: u8, part: Part) -> Option<&str> {
+        self.days.get(&day_key(day))?.get(&part_key(part))?.actual.as_deref()
+    }
+
+    pub fn record(&mut self, day: u8, part: Part, answer: &str) {
+        let entry = self.days.entry(day_key(day)).or_default()
+            .entry(part_key(part)).or_default();
+        entry.actual = Some(answer.to_string());
+    }
+}
\ No newline at end of file
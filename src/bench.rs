@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::solution::{Part, Runner};
+use crate::suite;
+use crate::suite::InputKind;
+use crate::utils;
+use crate::error::Error;
+
+pub static BASELINES: &str = "benchmarks.toml";
+
+pub struct BenchOptions {
+    /// Runs before measuring, to warm up caches and the allocator.
+    pub warmup: u32,
+    pub iterations: u32,
+    /// How much slower than its baseline, in percent, a run may get before it counts as a regression.
+    pub threshold: f64
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions { warmup: 3, iterations: 20, threshold: 10.0 }
+    }
+}
+
+/// Summary of one phase over all measured iterations.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Stats {
+    pub mean_ns: u64,
+    pub median_ns: u64,
+    pub p95_ns: u64
+}
+
+impl Stats {
+    /// Returns `None` for an empty sample.
+    pub fn of(samples: &[Duration]) -> Option<Stats> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut nanos = samples.iter().map(|d| d.as_nanos() as u64).collect::<Vec<u64>>();
+        nanos.sort_unstable();
+        let percentile = |p: usize| nanos[((nanos.len() * p).div_ceil(100)).clamp(1, nanos.len()) - 1];
+        Some(Stats {
+            mean_ns: nanos.iter().sum::<u64>() / nanos.len() as u64,
+            median_ns: percentile(50),
+            p95_ns: percentile(95)
+        })
+    }
+}
+
+/// What a bench run is compared against, as stored in [`BASELINES`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Baseline {
+    pub input_hash: String,
+    pub iterations: u32,
+    pub parse: Stats,
+    pub solve: Stats
+}
+
+impl Baseline {
+    fn median_ns(&self) -> u64 {
+        self.parse.median_ns + self.solve.median_ns
+    }
+}
+
+/// Baselines per day, part and input kind, stored as `[dayNN.partN.kind]` tables.
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(transparent)]
+pub struct Baselines {
+    days: BTreeMap<String, BTreeMap<String, BTreeMap<String, Baseline>>>
+}
+
+impl Baselines {
+    pub fn load(path: &str) -> Result<Baselines, Error> {
+        if !Path::new(path).exists() {
+            return Ok(Baselines::default());
+        }
+        toml::from_str(&utils::read_file(path)?)
+            .map_err(|e| Error::store(format!("Failed to parse '{}'", path), e))
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        let content = toml::to_string(self)
+            .map_err(|e| Error::store("Failed to serialize baselines", e))?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    pub fn get(&self, day: u8, part: Part, kind: InputKind) -> Option<&Baseline> {
+        self.days.get(&format!("day{:02}", day))?
+            .get(&format!("part{}", part))?
+            .get(&kind.to_string())
+    }
+
+    pub fn set(&mut self, day: u8, part: Part, kind: InputKind, baseline: Baseline) {
+        self.days.entry(format!("day{:02}", day)).or_default()
+            .entry(format!("part{}", part)).or_default()
+            .insert(kind.to_string(), baseline);
+    }
+}
+
+pub enum Comparison {
+    /// There is nothing to compare against yet.
+    New,
+    /// The baseline was measured on a different input.
+    InputChanged,
+    /// Relative change of the median total time, in percent. Positive means slower.
+    Changed { percent: f64, regressed: bool }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Comparison::New => write!(f, "new"),
+            Comparison::InputChanged => write!(f, "input changed"),
+            Comparison::Changed { percent, regressed: true } => write!(f, "REGRESSED {:+.1}%", percent),
+            Comparison::Changed { percent, regressed: false } => write!(f, "{:+.1}%", percent)
+        }
+    }
+}
+
+pub struct BenchResult {
+    pub day: u8,
+    pub part: Part,
+    pub kind: InputKind,
+    pub outcome: Result<Baseline, Error>,
+    pub comparison: Comparison
+}
+
+impl BenchResult {
+    pub fn is_regression(&self) -> bool {
+        matches!(self.comparison, Comparison::Changed { regressed: true, .. })
+    }
+}
+
+/// Runs one part `options.warmup + options.iterations` times and measures the last `iterations` runs.
+/// Stops at the first error, since a failing solution has no meaningful timing.
+pub fn measure(solution: &dyn Runner, part: Part, kind: InputKind, options: &BenchOptions) -> Result<Baseline, Error> {
+    let source = kind.source();
+    let input = source.read(solution.day())?;
+    let mut params = solution.default_params(&source);
+    params.merge(&source.params(solution.day()));
+    let mut parse_times = vec![];
+    let mut solve_times = vec![];
+    for i in 0..options.warmup + options.iterations {
+        let outcome = solution.run(&input, part, &params);
+        outcome.answer?;
+        if i >= options.warmup {
+            parse_times.push(outcome.parse_time);
+            solve_times.push(outcome.solve_time);
+        }
+    }
+    let empty = || Error::Usage("Nothing was measured (--iterations is 0)".to_string());
+    Ok(Baseline {
+        input_hash: utils::hash_hex(&input),
+        iterations: options.iterations,
+        parse: Stats::of(&parse_times).ok_or_else(empty)?,
+        solve: Stats::of(&solve_times).ok_or_else(empty)?
+    })
+}
+
+pub fn compare(current: &Baseline, baseline: Option<&Baseline>, threshold: f64) -> Comparison {
+    match baseline {
+        None => Comparison::New,
+        Some(baseline) if baseline.input_hash != current.input_hash => Comparison::InputChanged,
+        Some(baseline) => {
+            // Divides last, so a change of exactly `threshold` percent is computed exactly and does not count.
+            let (now, then) = (current.median_ns() as f64, baseline.median_ns().max(1) as f64);
+            let percent = (now - then) * 100.0 / then;
+            Comparison::Changed { percent, regressed: percent > threshold }
+        }
+    }
+}
+
+/// Benchmarks every given solution and part on every kind of input.
+pub fn run(solutions: &[&dyn Runner], parts: &[Part], kinds: &[InputKind], baselines: &Baselines, options: &BenchOptions) -> Vec<BenchResult> {
+    let mut results = vec![];
+    for &solution in solutions {
+        for &part in parts {
+            for &kind in kinds {
+                let outcome = measure(solution, part, kind, options);
+                let comparison = match &outcome {
+                    Ok(current) => compare(current, baselines.get(solution.day(), part, kind), options.threshold),
+                    Err(_) => Comparison::New
+                };
+                results.push(BenchResult { day: solution.day(), part, kind, outcome, comparison });
+            }
+        }
+    }
+    results
+}
+
+/// Makes every successful result the new baseline. Returns how many were stored.
+pub fn update_baselines(results: &[BenchResult], baselines: &mut Baselines) -> usize {
+    let mut stored = 0;
+    for r in results {
+        if let Ok(current) = &r.outcome {
+            baselines.set(r.day, r.part, r.kind, current.clone());
+            stored += 1;
+        }
+    }
+    stored
+}
+
+/// The machine-readable form of a [`BenchResult`], printed by `--format json`.
+#[derive(Serialize)]
+pub struct BenchReport {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub error: Option<String>,
+    pub iterations: Option<u32>,
+    pub parse: Option<Stats>,
+    pub solve: Option<Stats>,
+    pub change_percent: Option<f64>,
+    pub regressed: bool
+}
+
+impl From<&BenchResult> for BenchReport {
+    fn from(r: &BenchResult) -> Self {
+        let (error, current) = match &r.outcome {
+            Ok(current) => (None, Some(current)),
+            Err(err) => (Some(err.to_string()), None)
+        };
+        BenchReport {
+            day: r.day,
+            part: r.part.number(),
+            input: r.kind.to_string(),
+            error,
+            iterations: current.map(|c| c.iterations),
+            parse: current.map(|c| c.parse),
+            solve: current.map(|c| c.solve),
+            change_percent: match r.comparison {
+                Comparison::Changed { percent, .. } => Some(percent),
+                _ => None
+            },
+            regressed: r.is_regression()
+        }
+    }
+}
+
+pub fn print_results(results: &[BenchResult]) {
+    let header = ["Day", "Part", "Input", "Parse mean", "Parse median", "Parse p95", "Solve mean", "Solve median", "Solve p95", "vs baseline"];
+    let nanos = |ns: u64| suite::format_duration(Duration::from_nanos(ns));
+    let rows = results.iter().map(|r| {
+        let mut row = [
+            r.day.to_string(),
+            r.part.to_string(),
+            r.kind.to_string(),
+            String::new(), String::new(), String::new(),
+            String::new(), String::new(), String::new(),
+            r.comparison.to_string()
+        ];
+        match &r.outcome {
+            Ok(current) => {
+                row[3..9].clone_from_slice(&[
+                    nanos(current.parse.mean_ns),
+                    nanos(current.parse.median_ns),
+                    nanos(current.parse.p95_ns),
+                    nanos(current.solve.mean_ns),
+                    nanos(current.solve.median_ns),
+                    nanos(current.solve.p95_ns)
+                ]);
+            },
+            Err(err) => {
+                row[3] = suite::truncate(&err.to_string(), 40);
+                row[9] = "-".to_string();
+            }
+        }
+        row
+    }).collect::<Vec<[String; 10]>>();
+    suite::print_rows(header, &rows, None);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    fn baseline(hash: &str, parse_ns: u64, solve_ns: u64) -> Baseline {
+        let stats = |ns| Stats { mean_ns: ns, median_ns: ns, p95_ns: ns };
+        Baseline { input_hash: hash.to_string(), iterations: 10, parse: stats(parse_ns), solve: stats(solve_ns) }
+    }
+
+    #[test]
+    fn stats_summarize_the_sample() {
+        assert!(Stats::of(&[]).is_none());
+        let one = Stats::of(&millis(&[7])).unwrap();
+        assert_eq!((one.mean_ns, one.median_ns, one.p95_ns), (7_000_000, 7_000_000, 7_000_000));
+        let stats = Stats::of(&millis(&[5, 1, 4, 2, 3])).unwrap();
+        assert_eq!((stats.mean_ns, stats.median_ns, stats.p95_ns), (3_000_000, 3_000_000, 5_000_000));
+        let hundred = Stats::of(&millis(&(1..=100).rev().collect::<Vec<u64>>())).unwrap();
+        assert_eq!((hundred.median_ns, hundred.p95_ns), (50_000_000, 95_000_000));
+    }
+
+    #[test]
+    fn compare_regresses_only_beyond_the_threshold() {
+        let old = baseline("abc", 400, 600);
+        assert!(matches!(compare(&old, None, 10.0), Comparison::New));
+        assert!(matches!(compare(&baseline("def", 400, 600), Some(&old), 10.0), Comparison::InputChanged));
+        let at = compare(&baseline("abc", 500, 600), Some(&old), 10.0);
+        assert!(matches!(at, Comparison::Changed { percent, regressed: false } if percent == 10.0), "{}", at);
+        let over = compare(&baseline("abc", 501, 600), Some(&old), 10.0);
+        assert!(matches!(over, Comparison::Changed { regressed: true, .. }), "{}", over);
+        let faster = compare(&baseline("abc", 200, 300), Some(&old), 10.0);
+        assert!(matches!(faster, Comparison::Changed { percent, regressed: false } if percent == -50.0), "{}", faster);
+    }
+
+    #[test]
+    fn baselines_survive_a_round_trip() {
+        let dir = env::temp_dir().join(format!("aoc-bench-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("benchmarks.toml").to_string_lossy().to_string();
+        let _ = fs::remove_file(&path);
+        assert!(Baselines::load(&path).unwrap().get(1, Part::One, InputKind::Actual).is_none());
+
+        let mut baselines = Baselines::default();
+        baselines.set(1, Part::One, InputKind::Actual, baseline("abc", 400, 600));
+        baselines.set(12, Part::Two, InputKind::Sample, baseline("def", 1, 2));
+        baselines.save(&path).unwrap();
+        assert!(fs::read_to_string(&path).unwrap().contains("[day01.part1.actual"));
+
+        let loaded = Baselines::load(&path).unwrap();
+        let first = loaded.get(1, Part::One, InputKind::Actual).unwrap();
+        assert_eq!((first.input_hash.as_str(), first.median_ns()), ("abc", 1000));
+        assert_eq!(loaded.get(12, Part::Two, InputKind::Sample).map(|b| b.solve.p95_ns), Some(2));
+        assert!(loaded.get(12, Part::One, InputKind::Sample).is_none());
+
+        fs::write(&path, "day01 = 3").unwrap();
+        assert!(matches!(Baselines::load(&path), Err(Error::Store { .. })));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,302 @@
+﻿use std::num::NonZeroU32;
+use std::time::Duration;
+use aoc::bench::BenchOptions;
+use aoc::input::InputSource;
+use aoc::output::OutputSpec;
+use aoc::solution::{Params, Part};
+use aoc::suite::InputKind;
+use aoc::error::Error;
+
+pub static USAGE: &str = "Usage:
+    aoc run <day> <part> [INPUT] [--param NAME=VALUE]... [--output OUTPUT]... [--format text|json]
+    aoc all [--sample|--actual] [--timeout SECS] [--jobs N] [--format text|json]
+    aoc verify [--sample|--actual] [--timeout SECS] [--jobs N] [--record] [--format text|json]
+    aoc samples [day] [--timeout SECS] [--format text|json]
+    aoc bench [day] [part] [--sample|--actual] [--warmup N] [--iterations N] [--threshold PCT] [--save] [--format text|json]
+    aoc new <day> [--expect ANSWER]   (ANSWER is part 1's answer for the sample)
+    aoc lint <day> [INPUT]
+    aoc fetch <day>
+    aoc submit <day> <part> [ANSWER]   (defaults to the answer on the clipboard)
+
+INPUT is one of:
+    --actual          inputs/dayNN.txt (default)
+    --sample[=NAME]   the sample called NAME in samples.toml, or the day's first one
+    --input PATH      any file
+    --stdin           read the input from standard input
+    --inline TEXT     use TEXT as the input
+
+OUTPUT is one of stdout, clipboard, file:PATH or jsonl:PATH.
+Defaults to stdout and, if built with the 'clipboard' feature, the clipboard.
+With --format json, stdout gets one JSON object per day and part instead.
+
+Built with the 'embed-inputs' feature, inputs/ files and samples.toml as they were at build time
+are read from the binary.
+
+lint checks an input for problems like trailing whitespace, tabs, ragged grid rows, missing
+blank lines and numbers too large for the solution, runs the solution's parser on it,
+and lists the problems by line.
+
+samples checks the named examples in samples.toml against the answers given there.
+
+With --timeout, all, verify and samples report a part that runs longer than SECS as timed out and move on.
+With --jobs, they run up to N parts at once. Results keep their order, but peak memory is not reported.
+
+bench compares against the baselines in benchmarks.toml and fails if the median
+gets more than --threshold percent (default 10) slower. --save stores the new timings.
+
+Exit codes: 1 failed (e.g. a wrong answer in verify, or a part without an answer in all), 2 invalid usage,
+3 file error, 4 invalid input, 5 no solution, 6 network error, 7 cancelled.";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Tables and plain answers for people.
+    Text,
+    /// One JSON object per day and part, for scripts.
+    Json
+}
+
+pub struct SuiteArgs {
+    pub kinds: Vec<InputKind>,
+    pub format: Format,
+    /// How long a single day and part may run. Not supported by `bench`.
+    pub timeout: Option<Duration>,
+    /// How many days and parts run at once. Not supported by `bench`.
+    pub jobs: usize
+}
+
+impl Default for SuiteArgs {
+    fn default() -> Self {
+        SuiteArgs { kinds: vec![InputKind::Sample, InputKind::Actual], format: Format::Text, timeout: None, jobs: 1 }
+    }
+}
+
+pub enum Command {
+    Run { day: u8, part: Part, input: InputSource, params: Params, outputs: Vec<OutputSpec>, format: Format },
+    All { suite: SuiteArgs },
+    Verify { suite: SuiteArgs, record: bool },
+    Samples { day: Option<u8>, timeout: Option<Duration>, format: Format },
+    Bench { day: Option<u8>, part: Option<Part>, suite: SuiteArgs, options: BenchOptions, save: bool },
+    New { day: u8, expect: Option<String> },
+    Lint { day: u8, input: InputSource },
+    Fetch { day: u8 },
+    Submit { day: u8, part: Part, answer: Option<String> },
+    Help
+}
+
+pub fn parse_args<I: Iterator<Item=String>>(mut args: I) -> Result<Command, Error> {
+    match args.next().as_deref() {
+        Some("run") => parse_run(args),
+        Some("all") => parse_all(args),
+        Some("verify") => parse_verify(args),
+        Some("samples") => parse_samples(args),
+        Some("bench") => parse_bench(args),
+        Some("new") => parse_new(args),
+        Some("lint") => parse_lint(args),
+        Some("fetch") => Ok(Command::Fetch { day: parse_number(args.next(), "day")? }),
+        Some("submit") => Ok(Command::Submit {
+            day: parse_number(args.next(), "day")?,
+            part: Part::try_from(parse_number(args.next(), "part")?)?,
+            answer: args.next()
+        }),
+        Some("help") | Some("-h") | Some("--help") | None => Ok(Command::Help),
+        Some(other) => Err(Error::Usage(format!("Unknown command: {}", other)))
+    }
+}
+
+fn parse_run<I: Iterator<Item=String>>(mut args: I) -> Result<Command, Error> {
+    let day = parse_number(args.next(), "day")?;
+    let part = Part::try_from(parse_number(args.next(), "part")?)?;
+    let mut input = InputSource::Actual;
+    let mut params = Params::default();
+    let mut outputs = vec![];
+    let mut format = Format::Text;
+    while let Some(arg) = args.next() {
+        if parse_input_arg(&arg, &mut args, &mut input)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--param" => params.merge(&args.next()
+                .ok_or_else(|| Error::Usage("--param requires NAME=VALUE".to_string()))?
+                .parse()?),
+            "--format" => format = parse_format(args.next())?,
+            "--output" => outputs.push(args.next()
+                .ok_or_else(|| Error::Usage("--output requires a sink".to_string()))?
+                .parse()?),
+            other => return Err(Error::Usage(format!("Unknown argument: {}", other)))
+        }
+    }
+    if outputs.is_empty() {
+        outputs = OutputSpec::defaults();
+    }
+    Ok(Command::Run { day, part, input, params, outputs, format })
+}
+
+/// Handles the INPUT options of `run` and `lint`. Returns false if `arg` is not one of them.
+fn parse_input_arg<I: Iterator<Item=String>>(arg: &str, args: &mut I, input: &mut InputSource) -> Result<bool, Error> {
+    match arg {
+        "--sample" => *input = InputSource::Sample(None),
+        "--actual" => *input = InputSource::Actual,
+        "--stdin" => *input = InputSource::Stdin,
+        "--input" => *input = InputSource::Path(args.next()
+            .ok_or_else(|| Error::Usage("--input requires a path".to_string()))?),
+        "--inline" => *input = InputSource::Inline(args.next()
+            .ok_or_else(|| Error::Usage("--inline requires the input text".to_string()))?),
+        named if named.starts_with("--sample=") => *input = InputSource::Sample(
+            Some(named["--sample=".len()..].to_string())),
+        _ => return Ok(false)
+    }
+    Ok(true)
+}
+
+fn parse_lint<I: Iterator<Item=String>>(mut args: I) -> Result<Command, Error> {
+    let day = parse_number(args.next(), "day")?;
+    let mut input = InputSource::Actual;
+    while let Some(arg) = args.next() {
+        if !parse_input_arg(&arg, &mut args, &mut input)? {
+            return Err(Error::Usage(format!("Unknown argument: {}", arg)));
+        }
+    }
+    Ok(Command::Lint { day, input })
+}
+
+/// Handles the options `all` and `verify` share. Returns false if `arg` is not one of them.
+fn parse_suite_arg<I: Iterator<Item=String>>(arg: &str, args: &mut I, suite: &mut SuiteArgs) -> Result<bool, Error> {
+    match arg {
+        "--sample" => suite.kinds = vec![InputKind::Sample],
+        "--actual" => suite.kinds = vec![InputKind::Actual],
+        "--format" => suite.format = parse_format(args.next())?,
+        _ => return Ok(false)
+    }
+    Ok(true)
+}
+
+/// Handles the options of the commands that run the whole suite once, on top of [`parse_suite_arg`].
+fn parse_run_all_arg<I: Iterator<Item=String>>(arg: &str, args: &mut I, suite: &mut SuiteArgs) -> Result<bool, Error> {
+    match arg {
+        "--timeout" => suite.timeout = Some(parse_timeout(args.next())?),
+        "--jobs" => suite.jobs = match parse_value::<usize>(args.next(), "--jobs")? {
+            0 => return Err(Error::Usage("--jobs must be at least 1".to_string())),
+            jobs => jobs
+        },
+        _ => return parse_suite_arg(arg, args, suite)
+    }
+    Ok(true)
+}
+
+fn parse_all<I: Iterator<Item=String>>(mut args: I) -> Result<Command, Error> {
+    let mut suite = SuiteArgs::default();
+    while let Some(arg) = args.next() {
+        if !parse_run_all_arg(&arg, &mut args, &mut suite)? {
+            return Err(Error::Usage(format!("Unknown argument: {}", arg)));
+        }
+    }
+    Ok(Command::All { suite })
+}
+
+fn parse_verify<I: Iterator<Item=String>>(mut args: I) -> Result<Command, Error> {
+    let mut suite = SuiteArgs::default();
+    let mut record = false;
+    while let Some(arg) = args.next() {
+        if arg == "--record" {
+            record = true;
+        } else if !parse_run_all_arg(&arg, &mut args, &mut suite)? {
+            return Err(Error::Usage(format!("Unknown argument: {}", arg)));
+        }
+    }
+    Ok(Command::Verify { suite, record })
+}
+
+fn parse_samples<I: Iterator<Item=String>>(args: I) -> Result<Command, Error> {
+    let mut args = args.peekable();
+    let day = args.next_if(|a| !a.starts_with("--")).map(|a| parse_number(Some(a), "day")).transpose()?;
+    let mut timeout = None;
+    let mut format = Format::Text;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--timeout" => timeout = Some(parse_timeout(args.next())?),
+            "--format" => format = parse_format(args.next())?,
+            other => return Err(Error::Usage(format!("Unknown argument: {}", other)))
+        }
+    }
+    Ok(Command::Samples { day, timeout, format })
+}
+
+fn parse_bench<I: Iterator<Item=String>>(args: I) -> Result<Command, Error> {
+    let mut args = args.peekable();
+    let mut positional = || args.next_if(|a| !a.starts_with("--"));
+    let day = positional().map(|a| parse_number(Some(a), "day")).transpose()?;
+    let part = positional().map(|a| parse_number(Some(a), "part").and_then(Part::try_from)).transpose()?;
+    let mut suite = SuiteArgs { kinds: vec![InputKind::Actual], ..SuiteArgs::default() };
+    let mut options = BenchOptions::default();
+    let mut save = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--warmup" => options.warmup = parse_value(args.next(), "--warmup")?,
+            "--iterations" => options.iterations = parse_value::<NonZeroU32>(args.next(), "--iterations")?.get(),
+            "--threshold" => options.threshold = parse_value(args.next(), "--threshold")?,
+            "--save" => save = true,
+            _ => if !parse_suite_arg(&arg, &mut args, &mut suite)? {
+                return Err(Error::Usage(format!("Unknown argument: {}", arg)));
+            }
+        }
+    }
+    Ok(Command::Bench { day, part, suite, options, save })
+}
+
+fn parse_new<I: Iterator<Item=String>>(mut args: I) -> Result<Command, Error> {
+    let day = parse_number(args.next(), "day")?;
+    let mut expect = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--expect" => expect = Some(args.next()
+                .ok_or_else(|| Error::Usage("--expect requires an answer".to_string()))?),
+            other => return Err(Error::Usage(format!("Unknown argument: {}", other)))
+        }
+    }
+    Ok(Command::New { day, expect })
+}
+
+fn parse_format(arg: Option<String>) -> Result<Format, Error> {
+    match arg.as_deref() {
+        Some("text") => Ok(Format::Text),
+        Some("json") => Ok(Format::Json),
+        Some(other) => Err(Error::Usage(format!("Invalid format: {} (expected text or json)", other))),
+        None => Err(Error::Usage("--format requires text or json".to_string()))
+    }
+}
+
+fn parse_timeout(arg: Option<String>) -> Result<Duration, Error> {
+    let seconds = parse_value::<f64>(arg, "--timeout")?;
+    Duration::try_from_secs_f64(seconds)
+        .ok()
+        .filter(|timeout| !timeout.is_zero())
+        .ok_or_else(|| Error::Usage(format!("Invalid value for --timeout: {}", seconds)))
+}
+
+fn parse_value<T: std::str::FromStr>(arg: Option<String>, name: &str) -> Result<T, Error> {
+    let arg = arg.ok_or_else(|| Error::Usage(format!("{} requires a value", name)))?;
+    arg.parse::<T>()
+        .map_err(|_| Error::Usage(format!("Invalid value for {}: {}", name, arg)))
+}
+
+fn parse_number(arg: Option<String>, name: &str) -> Result<u8, Error> {
+    let arg = arg.ok_or_else(|| Error::Usage(format!("Missing <{}>", name)))?;
+    arg.parse::<u8>()
+        .map_err(|_| Error::Usage(format!("Invalid {}: {}", name, arg)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bench(args: &[&str]) -> Result<Command, Error> {
+        parse_args(["bench"].iter().chain(args).map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn bench_needs_at_least_one_iteration() {
+        assert!(matches!(bench(&["1", "--iterations", "5"]), Ok(Command::Bench { options, .. }) if options.iterations == 5));
+        assert!(matches!(bench(&["1", "--iterations", "0"]), Err(Error::Usage(_))));
+        assert!(matches!(bench(&["--warmup", "0"]), Ok(Command::Bench { options, .. }) if options.warmup == 0));
+    }
+}
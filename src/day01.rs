@@ -1,51 +1,57 @@
 ﻿use std::str::FromStr;
+use crate::solution::{Params, Solution};
+use crate::error::Error;
 use crate::utils;
-use crate::utils::ErrorMsg;
 
-static SAMPLE: &str = "inputs/day01_sample.txt";
-static ACTUAL: &str = "inputs/day01.txt";
-
-struct Rotation {
-    diff: i32,
+pub struct Rotation {
+    pub diff: i32,
 }
 
 impl FromStr for Rotation {
-    type Err = ErrorMsg;
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (dir, val_str) = s.trim().split_at(1);
-        let val = val_str.parse::<i32>()?;
+        let s = s.trim();
+        let (dir, val_str) = s.split_at(1);
+        let val = val_str.parse::<i32>()
+            .map_err(|e| Error::from(e).in_span(s, dir.len()..s.len()))?;
         let diff = match dir {
             "L" => -val,
             "R" => val,
-            _ => return Err(ErrorMsg{wrapped: format!("Invalid rotation direction: {}", dir)}),
+            _ => return Err(Error::Parse(format!("Invalid rotation direction: {}", dir)).in_span(s, 0..dir.len())),
         };
         Ok(Rotation{diff})
     }
 }
 
-pub fn run_part_1(actual: bool) -> Result<String, ErrorMsg> {
-    let mut password = 0;
-    let mut position = 50;
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<Rotation>;
 
-    for line in utils::read_file(if actual {ACTUAL} else {SAMPLE})?.split('\n') {
-        if line.len() > 0 {
-            let rot = line.parse::<Rotation>()?;
+    fn day(&self) -> u8 { 1 }
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        utils::parse_lines(input)
+    }
+
+    fn part_1(&self, rotations: &Self::Input, _: &Params) -> Result<String, Error> {
+        let mut password = 0;
+        let mut position = 50;
+
+        for rot in rotations {
             position = (position + rot.diff) % 100;
             if position == 0 {
                 password += 1;
             }
         }
+        Ok(password.to_string())
     }
-    Ok(password.to_string())
-}
 
-pub fn run_part_2(actual: bool) -> Result<String, ErrorMsg> {
-    let mut password = 0;
-    let mut position = 50;
+    fn part_2(&self, rotations: &Self::Input, _: &Params) -> Result<String, Error> {
+        let mut password = 0;
+        let mut position = 50;
 
-    for line in utils::read_file(if actual {ACTUAL} else {SAMPLE})?.split('\n') {
-        if line.len() > 0 {
-            let rot = line.parse::<Rotation>()?;
+        for rot in rotations {
             let prev_position = position;
             position += rot.diff;
             match position {
@@ -70,6 +76,6 @@ pub fn run_part_2(actual: bool) -> Result<String, ErrorMsg> {
                 _ => (),
             }
         }
+        Ok(password.to_string())
     }
-    Ok(password.to_string())
 }
\ No newline at end of file
@@ -1,33 +1,16 @@
 ﻿use std::iter::Filter;
 use std::ops::RangeInclusive;
-use std::str::FromStr;
-use crate::utils::ErrorMsg;
+use crate::solution::{Params, Solution};
+use crate::error::Error;
+use crate::utils;
+use crate::utils::interval::Interval;
 
-static SAMPLE: &str = "inputs/day02_sample.txt";
-static ACTUAL: &str = "inputs/day02.txt";
-
-struct Range {
-    first: u64,
-    last: u64
-}
-
-impl FromStr for Range {
-    type Err = ErrorMsg;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (first_str, last_str) = s.trim().split_once('-')
-            .ok_or_else(|| ErrorMsg{wrapped: format!("Invalid range format: {}", s)})?;
-        let first = first_str.parse()?;
-        let last = last_str.parse()?;
-        Ok(Range{first, last})
-    }
-}
-
-fn is_valid_id_v1(id: &u64) -> bool {
+pub fn is_valid_id_v1(id: &u64) -> bool {
     let mut digits = id.ilog10();
     if 10u64.pow(digits) != *id {
         digits += 1;
     }
-    if (digits % 2) != 0 {
+    if !digits.is_multiple_of(2) {
         return true;
     }
     let slice_size = digits / 2;
@@ -35,10 +18,10 @@ fn is_valid_id_v1(id: &u64) -> bool {
     id % mask != id / mask
 }
 
-fn is_valid_id_v2(id: &u64) -> bool {
+pub fn is_valid_id_v2(id: &u64) -> bool {
     let digits = id.ilog10() + 1;
     for slice_size in 1..=(digits / 2) {
-        if digits % slice_size != 0 {
+        if !digits.is_multiple_of(slice_size) {
             continue;
         }
         let mask = 10u64.pow(slice_size);
@@ -59,33 +42,37 @@ fn is_valid_id_v2(id: &u64) -> bool {
     true
 }
 
-impl Range {
-    fn invalids_v1(&self) -> Filter<RangeInclusive<u64>, fn(&u64) -> bool> {
-        (self.first..=self.last)
-            .filter(|id| !is_valid_id_v1(id))
-    }
-    fn invalids_v2(&self) -> Filter<RangeInclusive<u64>, fn(&u64) -> bool> {
-        (self.first..=self.last)
-            .filter(|id| !is_valid_id_v2(id))
-    }
+pub fn invalids_v1(range: &Interval<u64>) -> Filter<RangeInclusive<u64>, fn(&u64) -> bool> {
+    range.range().filter(|id| !is_valid_id_v1(id))
 }
 
-fn collect_ranges(actual: bool) -> Result<Vec<Range>, ErrorMsg> {
-    let ranges = crate::utils::read_file(if actual {ACTUAL} else {SAMPLE})?
-        .split(',')
-        .map(str::parse::<Range>)
-        .collect::<Result<Vec<Range>, ErrorMsg>>()?;
-    Ok(ranges)
+pub fn invalids_v2(range: &Interval<u64>) -> Filter<RangeInclusive<u64>, fn(&u64) -> bool> {
+    range.range().filter(|id| !is_valid_id_v2(id))
 }
 
-pub fn run_part_1(actual: bool) -> Result<String, ErrorMsg> {
-    let ranges = collect_ranges(actual)?;
-    let num_invalids: u64 = ranges.iter().flat_map(|r| r.invalids_v1()).sum();
-    Ok(num_invalids.to_string())
-}
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<Interval<u64>>;
+
+    fn day(&self) -> u8 { 2 }
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        Ok(utils::lines(input)?.iter()
+            .map(|line| line.parse_split::<Interval<u64>>(','))
+            .collect::<Result<Vec<Vec<Interval<u64>>>, Error>>()?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    fn part_1(&self, ranges: &Self::Input, _: &Params) -> Result<String, Error> {
+        let num_invalids: u64 = ranges.iter().flat_map(invalids_v1).sum();
+        Ok(num_invalids.to_string())
+    }
 
-pub fn run_part_2(actual: bool) -> Result<String, ErrorMsg> {
-    let ranges = collect_ranges(actual)?;
-    let num_invalids: u64 = ranges.iter().flat_map(|r| r.invalids_v2()).sum();
-    Ok(num_invalids.to_string())
+    fn part_2(&self, ranges: &Self::Input, _: &Params) -> Result<String, Error> {
+        let num_invalids: u64 = ranges.iter().flat_map(invalids_v2).sum();
+        Ok(num_invalids.to_string())
+    }
 }
\ No newline at end of file
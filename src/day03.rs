@@ -1,47 +1,54 @@
 ﻿use std::str::FromStr;
+use crate::solution::{Params, Solution};
+use crate::error::Error;
 use crate::utils;
-use crate::utils::ErrorMsg;
 
-static SAMPLE: &str = "inputs/day03_sample.txt";
-static ACTUAL: &str = "inputs/day03.txt";
-
-struct Bank {
-    batteries: Vec<u8>
+pub struct Bank {
+    pub batteries: Vec<u8>
 }
 
 impl FromStr for Bank {
-    type Err = ErrorMsg;
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let batteries = s.trim().chars()
-            .map(|c| c.to_digit(10)
+        let batteries = s.char_indices()
+            .filter(|(_, c)| !c.is_whitespace())
+            .map(|(i, c)| c.to_digit(10)
                 .map(|d| d as u8)
-                .ok_or_else(|| ErrorMsg{wrapped: format!("Invalid battery character: {}", c)}))
-            .collect::<Result<Vec<u8>, ErrorMsg>>()?;
+                .ok_or_else(|| Error::Parse(format!("Invalid battery character: {}", c)).in_span(s, i..i + c.len_utf8())))
+            .collect::<Result<Vec<u8>, Error>>()?;
         Ok(Bank{batteries})
     }
 }
 
-pub fn run_part_1(actual: bool) -> Result<String, ErrorMsg> {
-    run(actual, 2)
-}
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Vec<Bank>;
 
-pub fn run_part_2(actual: bool) -> Result<String, ErrorMsg> {
-    run(actual, 12)
+    fn day(&self) -> u8 { 3 }
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        utils::parse_lines(input)
+    }
+
+    fn part_1(&self, banks: &Self::Input, _: &Params) -> Result<String, Error> {
+        run(banks, 2)
+    }
+
+    fn part_2(&self, banks: &Self::Input, _: &Params) -> Result<String, Error> {
+        run(banks, 12)
+    }
 }
 
-fn run(actual: bool, count: u8) -> Result<String, ErrorMsg> {
-    let banks = utils::read_file(if actual {ACTUAL} else {SAMPLE})?
-        .trim()
-        .split('\n')
-        .map(|s| s.parse::<Bank>())
-        .collect::<Result<Vec<Bank>, ErrorMsg>>()?;
+fn run(banks: &[Bank], count: u8) -> Result<String, Error> {
     let mut sum = 0;
     for bank in banks {
         let mut last: Option<u8> = None;
         let mut accum: u64 = 0;
         for i in 0..count {
-            let mut start = last.map(|l| l + 1).unwrap_or(0);
-            for j in start..=(bank.batteries.len() as u8 - count + i) {
+            let first = last.map(|l| l + 1).unwrap_or(0);
+            let mut start = first;
+            for j in first..=(bank.batteries.len() as u8 - count + i) {
                 if bank.batteries[j as usize] > bank.batteries[start as usize] {
                     start = j;
                 }
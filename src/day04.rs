@@ -1,101 +1,58 @@
-﻿use std::str::FromStr;
-use crate::utils;
-use crate::utils::ErrorMsg;
-
-static SAMPLE: &str = "inputs/day04_sample.txt";
-static ACTUAL: &str = "inputs/day04.txt";
+﻿use crate::solution::{Params, Solution};
+use crate::error::Error;
+use crate::lint;
+use crate::utils::grid::Grid;
+
+/// How many of the up to eight cells around `(row, col)` hold a roll of paper.
+pub fn adjacent_rolls(grid: &Grid<bool>, (row, col): (usize, usize)) -> usize {
+    grid.neighbours_8(row, col).filter(|&p| grid[p]).count()
+}
 
-struct Grid {
-    cells: Vec<Vec<bool>>
+/// Whether a forklift can reach the roll at `position`, if there is one.
+pub fn is_accessible(grid: &Grid<bool>, position: (usize, usize)) -> bool {
+    grid[position] && adjacent_rolls(grid, position) < 4
 }
 
-impl Grid {
-    fn get_at(&self, rowI: isize, colI: isize) -> bool {
-        if rowI < 0 || colI < 0 {
-            return false;
-        }
-        if let Some(row) = self.cells.get(rowI as usize) {
-            if let Some(val) = row.get(colI as usize) {
-                return *val;
-            }
-        }
-        false
-    }
+pub struct Day04;
 
-    fn deactivate_at(&mut self, rowI: usize, colI: usize) {
-        if let Some(row) = self.cells.get_mut(rowI) {
-            if let Some(val) = row.get_mut(colI) {
-                *val = false;
-            }
-        }
+impl Solution for Day04 {
+    type Input = Grid<bool>;
+
+    fn day(&self) -> u8 { 4 }
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        Grid::parse(input, |c| match c {
+            '@' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(Error::Parse(format!("Unrecognized cell {}", c)))
+        })
     }
 
-    fn adjacent_rolls(&self, rowI: usize, colI: usize) -> u8 {
-        let mut count = 0;
-        for r in -1..=1 {
-            for c in -1..=1 {
-                if r == 0 && c == 0 {
-                    continue;
-                }
-                if self.get_at(rowI as isize + r, colI as isize + c) {
-                    count += 1;
-                }
-            }
-        }
-        count
+    fn lint(&self, input: &str) -> Vec<Error> {
+        let mut problems = lint::whitespace(input);
+        problems.extend(lint::ragged_rows(input));
+        problems
     }
-}
 
-pub fn run_part_1(actual: bool) -> Result<String, ErrorMsg> {
-    let grid = parse_input(actual)?;
-    let mut accessible_rolls = 0;
-    for (rowI, row) in grid.cells.iter().enumerate() {
-        for colI in 0..row.len() {
-            if grid.get_at(rowI as isize, colI as isize) {
-                if grid.adjacent_rolls(rowI, colI) < 4 {
-                    accessible_rolls += 1;
-                }
-            }
-        }
+    fn part_1(&self, grid: &Self::Input, _: &Params) -> Result<String, Error> {
+        let accessible_rolls = grid.positions().filter(|&p| is_accessible(grid, p)).count();
+        Ok(accessible_rolls.to_string())
     }
-    Ok(accessible_rolls.to_string())
-}
 
-pub fn run_part_2(actual: bool) -> Result<String, ErrorMsg> {
-    let mut grid = parse_input(actual)?;
-    let width = grid.cells[0].len();
-    let height = grid.cells.len();
-    let mut accessible_rolls = 0;
-    let mut any_deactivated = true;
-    while any_deactivated {
-        any_deactivated = false;
-        for rowI in 0..height {
-            for colI in 0..width {
-                if grid.get_at(rowI as isize, colI as isize) {
-                    if grid.adjacent_rolls(rowI, colI) < 4 {
-                        accessible_rolls += 1;
-                        grid.deactivate_at(rowI, colI);
-                        any_deactivated = true;
-                    }
+    fn part_2(&self, grid: &Self::Input, _: &Params) -> Result<String, Error> {
+        let mut grid = grid.clone();
+        let mut accessible_rolls = 0;
+        let mut any_deactivated = true;
+        while any_deactivated {
+            any_deactivated = false;
+            for position in grid.positions() {
+                if is_accessible(&grid, position) {
+                    accessible_rolls += 1;
+                    grid[position] = false;
+                    any_deactivated = true;
                 }
             }
         }
+        Ok(accessible_rolls.to_string())
     }
-    Ok(accessible_rolls.to_string())
-}
-
-impl FromStr for Grid {
-    type Err = ErrorMsg;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let cells = s.trim()
-            .split('\n')
-            .map(|line| line.trim().chars().map(|c| c == '@').collect::<Vec<bool>>())
-            .collect::<Vec<Vec<bool>>>();
-        Ok(Grid{cells})
-    }
-}
-
-fn parse_input(actual: bool) -> Result<Grid, ErrorMsg> {
-    utils::read_file(if actual {ACTUAL} else {SAMPLE})?
-        .parse()
 }
\ No newline at end of file
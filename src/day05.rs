@@ -1,90 +1,51 @@
-﻿use std::cmp::{min,max};
-use std::num::ParseIntError;
-use std::str::FromStr;
+﻿use crate::solution::{Params, Solution};
+use crate::error::Error;
+use crate::lint;
 use crate::utils;
-use crate::utils::ErrorMsg;
+use crate::utils::Line;
+use crate::utils::interval::{Interval, IntervalSet};
 
-static SAMPLE: &str = "inputs/day05_sample.txt";
-static ACTUAL: &str = "inputs/day05.txt";
-
-struct Range {
-    first: u64,
-    last: u64
+pub struct Inventory {
+    /// Every fresh ingredient id.
+    pub fresh: IntervalSet<u64>,
+    pub ids: Vec<u64>
 }
 
-impl Range {
-    fn contains(&self, id: u64) -> bool {
-        id >= self.first && id <= self.last
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = Inventory;
+
+    fn day(&self) -> u8 { 5 }
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        let sections = utils::sections(input)?;
+        let [ranges, ids] = sections.as_slice() else {
+            return Err(Error::Parse(format!("Expected ranges and ids separated by an empty line, found {} section(s)", sections.len())));
+        };
+        let fresh = ranges.iter()
+            .map(Line::parse::<Interval<u64>>)
+            .collect::<Result<IntervalSet<u64>, Error>>()?;
+        let ids = ids.iter()
+            .map(Line::parse::<u64>)
+            .collect::<Result<Vec<u64>, Error>>()?;
+        Ok(Inventory{fresh, ids})
     }
 
-    fn overlap_with(&self, other: &Range) -> Option<Range> {
-        if self.first <= other.first && self.last >= other.first {
-            Some(Range{first: other.first, last: min(self.last, other.last)})
-        } else if other.first <= self.first && other.last >= self.first {
-            Some(Range{first: self.first, last: min(self.last, other.last)})
-        } else {
-            None
-        }
+    fn lint(&self, input: &str) -> Vec<Error> {
+        let mut problems = lint::whitespace(input);
+        problems.extend(lint::missing_separator(input, |line| !line.contains('-')));
+        problems
     }
-}
-
-impl FromStr for Range {
-    type Err = ErrorMsg;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (first_str, last_str) = s.trim().split_once('-')
-            .ok_or_else(|| ErrorMsg{wrapped: format!("No dash: {}", s)})?;
-        let first = first_str.parse()?;
-        let last = last_str.parse()?;
-        Ok(Range{first, last})
-    }}
-
-pub fn run_part_1(actual: bool) -> Result<String, ErrorMsg> {
-    let input = utils::read_file(if actual {ACTUAL} else {SAMPLE})?;
-    let (ranges_str, ids_str) = input
-        .trim()
-        .split_once("\n\n")
-        .ok_or_else(|| ErrorMsg{wrapped: format!("No empty line: {}", input.trim())})?;
-    let ranges = ranges_str.trim()
-        .split('\n')
-        .map(|s| s.trim().parse::<Range>())
-        .collect::<Result<Vec<Range>, ErrorMsg>>()?;
-    let ids = ids_str.trim()
-        .split('\n')
-        .map(|s| s.trim().parse::<u64>())
-        .collect::<Result<Vec<u64>, ParseIntError>>()?;
-
-    let fresh = ids.iter()
-        .filter(|&&id| ranges.iter().any(|r| r.contains(id)))
-        .count();
-    Ok(fresh.to_string())
-}
-
-pub fn run_part_2(actual: bool) -> Result<String, ErrorMsg> {
-    let input = utils::read_file(if actual {ACTUAL} else {SAMPLE})?;
-    let (ranges_str, ids_str) = input
-        .trim()
-        .split_once("\n\n")
-        .ok_or_else(|| ErrorMsg{wrapped: format!("No empty line: {}", input.trim())})?;
-
-    let mut ranges = ranges_str.trim()
-        .split('\n')
-        .map(|s| s.trim().parse::<Range>())
-        .collect::<Result<Vec<Range>, ErrorMsg>>()?;
 
-    ranges.sort_by(|a, b| a.first.partial_cmp(&b.first).unwrap());
-
-    let mut i = 0;
-    while i < ranges.len() - 1 {
-        if ranges[i].last < ranges[i + 1].first {
-            i += 1;
-        } else {
-            ranges[i].last = max(ranges[i].last, ranges[i + 1].last);
-            ranges.remove(i + 1);
-        }
+    fn part_1(&self, inventory: &Self::Input, _: &Params) -> Result<String, Error> {
+        let fresh = inventory.ids.iter()
+            .filter(|&&id| inventory.fresh.contains(id))
+            .count();
+        Ok(fresh.to_string())
     }
 
-    let fresh: u64 = ranges.iter()
-        .map(|r| r.last - r.first + 1)
-        .sum();
-    Ok(fresh.to_string())
+    fn part_2(&self, inventory: &Self::Input, _: &Params) -> Result<String, Error> {
+        Ok(inventory.fresh.total_len().to_string())
+    }
 }
\ No newline at end of file
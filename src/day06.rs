@@ -1,84 +1,100 @@
-﻿use std::num::ParseIntError;
+﻿use std::iter;
+use std::num::ParseIntError;
 use std::str::FromStr;
 use crate::day06::Operator::{Add, Mul};
+use crate::solution::{Params, Solution};
+use crate::error::Error;
+use crate::lint;
 use crate::utils;
-use crate::utils::ErrorMsg;
+use crate::utils::grid::Grid;
 
-static SAMPLE: &str = "inputs/day06_sample.txt";
-static ACTUAL: &str = "inputs/day06.txt";
-
-enum Operator {
+pub enum Operator {
     Mul, Add
 }
 
 impl FromStr for Operator {
-    type Err = ErrorMsg;
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.trim() {
             "*" => Ok(Mul),
             "+" => Ok(Add),
-            _ => Err(ErrorMsg{wrapped: format!("Invalid operator: {}", s)})
+            _ => Err(Error::Parse(format!("Invalid operator: {}", s)))
         }
     }
 }
 
-pub fn run_part_1(actual: bool) -> Result<String, ErrorMsg> {
-    let file = utils::read_file(if actual {ACTUAL} else {SAMPLE})?;
-    let lines = file.trim()
-        .split('\n')
-        .map(|l| l.split(" ")
-            .filter(|e| !e.is_empty())
-            .collect::<Vec<&str>>())
-        .collect::<Vec<Vec<&str>>>();
-    let (operators_line, argument_lines) = lines
-        .split_last().ok_or_else(|| ErrorMsg{wrapped: "Input empty".to_string()})?;
-    let arguments = argument_lines.iter()
-        .map(|line| line.iter()
-            .map(|e| e.parse::<u64>())
-            .collect::<Result<Vec<u64>, ParseIntError>>())
-        .collect::<Result<Vec<Vec<u64>>, ParseIntError>>()?;
-    let operators = operators_line.iter()
-        .map(|e| e.parse::<Operator>())
-        .collect::<Result<Vec<Operator>, ErrorMsg>>()?;
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Grid<char>;
+
+    fn day(&self) -> u8 { 6 }
 
-    let mut sum = 0;
-    for i in 0..operators.len() {
-        sum += match operators[i] {
-            Mul => arguments.iter().map(|a| a[i]).product::<u64>(),
-            Add => arguments.iter().map(|a| a[i]).sum()
-        };
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        let lines = utils::lines(input)?;
+        // Pads short lines with spaces, since editors may have dropped the ones at the end.
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        Grid::from_rows(lines.iter()
+            .map(|line| line.chars().chain(iter::repeat(' ')).take(width).collect())
+            .collect())
     }
 
-    Ok(sum.to_string())
-}
+    /// Only checks for tabs, since spaces at the end of lines keep the columns aligned.
+    fn lint(&self, input: &str) -> Vec<Error> {
+        lint::tabs(input)
+    }
 
-pub fn run_part_2(actual: bool) -> Result<String, ErrorMsg> {
-    let file = utils::read_file(if actual {ACTUAL} else {SAMPLE})?;
-    let lines = file
-        .split('\n')
-        .map(|l| l.chars().collect::<Vec<char>>())
-        .collect::<Vec<Vec<char>>>();
-    let (operators_line, argument_lines) = lines
-        .split_last().ok_or_else(|| ErrorMsg{wrapped: "Input empty".to_string()})?;
+    fn part_1(&self, grid: &Self::Input, _: &Params) -> Result<String, Error> {
+        let lines = grid.rows()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<String>>();
+        let lines = lines.iter()
+            .map(|l| l.split_whitespace().collect::<Vec<&str>>())
+            .collect::<Vec<Vec<&str>>>();
+        let (operators_line, argument_lines) = lines
+            .split_last().ok_or_else(|| Error::Parse("Input empty".to_string()))?;
+        let arguments = argument_lines.iter()
+            .map(|line| line.iter()
+                .map(|e| e.parse::<u64>())
+                .collect::<Result<Vec<u64>, ParseIntError>>())
+            .collect::<Result<Vec<Vec<u64>>, ParseIntError>>()?;
+        let operators = operators_line.iter()
+            .map(|e| e.parse::<Operator>())
+            .collect::<Result<Vec<Operator>, Error>>()?;
 
-    let mut sum = 0;
-    let mut args = vec![];
-    for i in (0..operators_line.len()).rev() {
-        let num = argument_lines.iter()
-            .flat_map(|l| l[i].to_digit(10))
-            .fold(0u64, |l, r| l * 10 + r as u64);
-        if num != 0 {
-            args.push(num);
-        }
-        if let Ok(op) = operators_line[i].to_string().parse::<Operator>(){
-            println!("{:?}", args);
-            sum += match op {
-                Mul => args.iter().product::<u64>(),
-                Add => args.iter().sum()
+        let mut sum = 0;
+        for i in 0..operators.len() {
+            sum += match operators[i] {
+                Mul => arguments.iter().map(|a| a[i]).product::<u64>(),
+                Add => arguments.iter().map(|a| a[i]).sum()
             };
-            args.clear()
         }
+
+        Ok(sum.to_string())
     }
 
-    Ok(sum.to_string())
+    fn part_2(&self, grid: &Self::Input, _: &Params) -> Result<String, Error> {
+        let operators_row = grid.height().checked_sub(1).ok_or_else(|| Error::Parse("Input empty".to_string()))?;
+
+        let mut sum = 0;
+        let mut args = vec![];
+        for i in (0..grid.width()).rev() {
+            let num = grid.column(i)
+                .take(operators_row)
+                .flat_map(|c| c.to_digit(10))
+                .fold(0u64, |l, r| l * 10 + r as u64);
+            if num != 0 {
+                args.push(num);
+            }
+            if let Ok(op) = grid[(operators_row, i)].to_string().parse::<Operator>(){
+                sum += match op {
+                    Mul => args.iter().product::<u64>(),
+                    Add => args.iter().sum()
+                };
+                args.clear()
+            }
+        }
+
+        Ok(sum.to_string())
+    }
 }
\ No newline at end of file
@@ -1,62 +1,100 @@
 ﻿use std::collections::{HashMap, HashSet};
-use crate::utils;
-use crate::utils::ErrorMsg;
-
-static SAMPLE: &str = "inputs/day07_sample.txt";
-static ACTUAL: &str = "inputs/day07.txt";
-
-pub fn run_part_1(actual: bool) -> Result<String, ErrorMsg> {
-    let file = utils::read_file(if actual {ACTUAL} else {SAMPLE})?;
-    let lines = file.trim()
-        .split('\n')
-        .collect::<Vec<&str>>();
-    let start_loc = lines[0].find('S').ok_or_else(|| ErrorMsg{wrapped: "No start".to_string()})?;
-    let mut beams = HashSet::new();
-    beams.insert(start_loc);
-    let mut split_count = 0;
-    for l in lines.iter().skip(1) {
-        let mut new_beams = HashSet::new();
-        for &b in beams.iter() {
-            match l.chars().nth(b).ok_or_else(|| ErrorMsg{wrapped: "Ran out of bounds".to_string()})? {
-                '.' => {
-                    new_beams.insert(b);
-                },
-                '^' => {
-                    new_beams.insert(b + 1);
-                    new_beams.insert(b - 1);
-                    split_count += 1;
+use crate::solution::{Params, Solution};
+use crate::error::Error;
+use crate::lint;
+use crate::utils::grid::Grid;
+
+pub struct Day07;
+
+/// The columns left and right of the splitter at `row` and `col`, or an error at the splitter if either is off the grid.
+fn split(grid: &Grid<char>, row: usize, col: usize) -> Result<[usize; 2], Error> {
+    match col.checked_sub(1) {
+        Some(left) if col + 1 < grid.width() => Ok([left, col + 1]),
+        _ => {
+            let line = grid.row(row).iter().collect::<String>();
+            let start = line.char_indices().nth(col).map_or(line.len(), |(i, _)| i);
+            Err(Error::Parse("Splitter sends a beam off the edge of the grid".to_string())
+                .at_line(row + 1, &line, start..start + 1))
+        }
+    }
+}
+
+impl Solution for Day07 {
+    type Input = Grid<char>;
+
+    fn day(&self) -> u8 { 7 }
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        input.parse()
+    }
+
+    fn lint(&self, input: &str) -> Vec<Error> {
+        let mut problems = lint::whitespace(input);
+        problems.extend(lint::ragged_rows(input));
+        problems
+    }
+
+    fn part_1(&self, grid: &Self::Input, _: &Params) -> Result<String, Error> {
+        let (start_row, start_col) = grid.find(&'S').ok_or_else(|| Error::Parse("No start".to_string()))?;
+        let mut beams = HashSet::new();
+        beams.insert(start_col);
+        let mut split_count = 0;
+        for row in start_row + 1..grid.height() {
+            let mut new_beams = HashSet::new();
+            for &b in beams.iter() {
+                match grid.get(row, b).ok_or_else(|| Error::Parse("Ran out of bounds".to_string()))? {
+                    '.' => {
+                        new_beams.insert(b);
+                    },
+                    '^' => {
+                        new_beams.extend(split(grid, row, b)?);
+                        split_count += 1;
+                    }
+                    other => return Err(Error::Parse(format!("Unknown symbol {}", other)))
                 }
-                other => return Err(ErrorMsg{wrapped: format!("Unknown symbol {}", other)})
             }
+            beams = new_beams;
         }
-        beams = new_beams;
+        Ok(split_count.to_string())
     }
-    Ok(split_count.to_string())
-}
 
-pub fn run_part_2(actual: bool) -> Result<String, ErrorMsg> {
-    let file = utils::read_file(if actual {ACTUAL} else {SAMPLE})?;
-    let lines = file.trim()
-        .split('\n')
-        .collect::<Vec<&str>>();
-    let start_loc = lines[0].find('S').ok_or_else(|| ErrorMsg{wrapped: "No start".to_string()})?;
-    let mut beams = HashMap::new();
-    beams.insert(start_loc, 1);
-    for l in lines.iter().skip(1) {
-        let mut new_beams = HashMap::new();
-        for (&b, &timeline_count) in beams.iter() {
-            match l.chars().nth(b).ok_or_else(|| ErrorMsg{wrapped: "Ran out of bounds".to_string()})? {
-                '.' => {
-                    *new_beams.entry(b).or_insert(0) += timeline_count;
-                },
-                '^' => {
-                    *new_beams.entry(b + 1).or_insert(0) += timeline_count;
-                    *new_beams.entry(b - 1).or_insert(0) += timeline_count;
+    fn part_2(&self, grid: &Self::Input, _: &Params) -> Result<String, Error> {
+        let (start_row, start_col) = grid.find(&'S').ok_or_else(|| Error::Parse("No start".to_string()))?;
+        let mut beams = HashMap::new();
+        beams.insert(start_col, 1);
+        for row in start_row + 1..grid.height() {
+            let mut new_beams = HashMap::new();
+            for (&b, &timeline_count) in beams.iter() {
+                match grid.get(row, b).ok_or_else(|| Error::Parse("Ran out of bounds".to_string()))? {
+                    '.' => {
+                        *new_beams.entry(b).or_insert(0) += timeline_count;
+                    },
+                    '^' => {
+                        for side in split(grid, row, b)? {
+                            *new_beams.entry(side).or_insert(0) += timeline_count;
+                        }
+                    }
+                    other => return Err(Error::Parse(format!("Unknown symbol {}", other)))
                 }
-                other => return Err(ErrorMsg{wrapped: format!("Unknown symbol {}", other)})
+            }
+            beams = new_beams;
+        }
+        Ok(beams.values().sum::<i64>().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splitters_at_the_edges_are_errors() {
+        for input in ["S..\n^..", "..S\n..^"] {
+            let grid = Day07.parse(input).unwrap();
+            for result in [Day07.part_1(&grid, &Params::default()), Day07.part_2(&grid, &Params::default())] {
+                let err = result.unwrap_err();
+                assert_eq!(err.location().and_then(|l| l.line), Some(2), "{}", input);
             }
         }
-        beams = new_beams;
     }
-    Ok(beams.values().into_iter().sum::<i64>().to_string())
-}
\ No newline at end of file
+}
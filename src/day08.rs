@@ -1,134 +1,80 @@
-﻿use crate::utils;
-use crate::utils::ErrorMsg;
-use std::cmp::{max, min};
-use std::str::FromStr;
-
-static SAMPLE: &str = "inputs/day08_sample.txt";
-static ACTUAL: &str = "inputs/day08.txt";
-
-struct Pos {
-    x: i32,
-    y: i32,
-    z: i32
-}
-
-impl Pos {
-    fn sqr_distance_to(&self, other: &Self) -> u64 {
-        let dx = (other.x - self.x).abs() as u64;
-        let dy = (other.y - self.y).abs() as u64;
-        let dz = (other.z - self.z).abs() as u64;
-        dx * dx + dy * dy + dz * dz
-    }
-}
-
-impl FromStr for Pos {
-    type Err = ErrorMsg;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let vals = s.trim().split(',').collect::<Vec<&str>>();
-        if vals.len() == 3 {
-            Ok(Pos {x: vals[0].parse()?, y: vals[1].parse()?, z: vals[2].parse()?})
-        } else {
-            Err(ErrorMsg{wrapped: format!("Contains no 2 commas: {}", s)})
-        }
-    }
-}
-
-struct Connection {
-    from: u16,
-    to: u16
+﻿use crate::input::InputSource;
+use crate::solution::{Params, Solution};
+use crate::error::Error;
+use crate::geom::Point3;
+use crate::lint;
+use crate::utils;
+use crate::utils::union_find::UnionFind;
+
+/// A junction box. Parsed as `i32`, then widened so squared distances cannot overflow.
+pub type Pos = Point3<i64>;
+
+pub struct Connection {
+    pub from: usize,
+    pub to: usize
 }
 
 impl Connection {
-    fn sqr_distance(&self, coords: &Vec<Pos>) -> u64 {
-        coords[self.from as usize]
-            .sqr_distance_to(&coords[self.to as usize])
+    pub fn sqr_distance(&self, coords: &[Pos]) -> u64 {
+        coords[self.from]
+            .euclidean_sq(&coords[self.to]) as u64
     }
 }
 
-fn read_coords(actual: bool) -> Result<Vec<Pos>, ErrorMsg> {
-    let file = utils::read_file(if actual {ACTUAL} else {SAMPLE})?;
-    file.trim()
-        .split('\n')
-        .map(|l| l.parse::<Pos>())
-        .collect::<Result<Vec<Pos>, ErrorMsg>>()
-}
-
-fn all_connections_sorted(coords: &Vec<Pos>) -> Vec<Connection> {
-    let mut connections = (0..coords.len() as u16)
-        .flat_map(|i| ((i+1)..coords.len() as u16)
+pub fn all_connections_sorted(coords: &[Pos]) -> Vec<Connection> {
+    let mut connections = (0..coords.len())
+        .flat_map(|i| ((i+1)..coords.len())
             .map(move |j| Connection {from: i, to: j}))
         .collect::<Vec<Connection>>();
-    connections.sort_by_key(|c| c.sqr_distance(&coords));
+    connections.sort_by_key(|c| c.sqr_distance(coords));
     connections
 }
 
-fn combine_circuits(connection: &Connection, circuits: &mut Vec<i16>, circuit_size: &mut Vec<u16>) {
-    let ca = circuits[connection.from as usize];
-    let cb = circuits[connection.to as usize];
-    if ca != -1 && ca == cb {
-        return;
-    }
+pub struct Day08;
 
-    match (ca, cb) {
-        (-1, -1) => {
-            let new_circuit = circuit_size.len() as i16;
-            circuits[connection.from as usize] = new_circuit;
-            circuits[connection.to as usize] = new_circuit;
-            circuit_size.push(2);
-        },
-        (a, -1) => {
-            circuits[connection.to as usize] = a;
-            circuit_size[a as usize] += 1;
-        },
-        (-1, b) => {
-            circuits[connection.from as usize] = b;
-            circuit_size[b as usize] += 1;
-        },
-        (a, b) => {
-            let keep = min(a, b);
-            let drop = max(a, b);
-            circuit_size[keep as usize] += circuit_size[drop as usize];
-            for e in circuits {
-                if *e == drop {
-                    *e = keep;
-                } else if *e > drop {
-                    *e -= 1;
-                }
-            }
-            circuit_size.remove(drop as usize);
-        }
-    }
-}
+impl Solution for Day08 {
+    type Input = Vec<Pos>;
 
-pub fn run_part_1(actual: bool, count: usize) -> Result<String, ErrorMsg> {
-    let coords = read_coords(actual)?;
-    let connections = all_connections_sorted(&coords);
+    fn day(&self) -> u8 { 8 }
 
-    let mut circuits = vec![-1i16; coords.len()];
-    let mut circuit_size: Vec<u16> = vec![];
+    fn default_params(&self, source: &InputSource) -> Params {
+        Params::default().with("count", if source.is_sample() { 10 } else { 1000 })
+    }
 
-    for connection in connections.iter().take(count) {
-        combine_circuits(connection, &mut circuits, &mut circuit_size);
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        utils::lines(input)?.iter()
+            .map(|line| line.parse::<Point3<i32>>().map(|p| p.map(i64::from)))
+            .collect()
     }
 
-    circuit_size.sort_by_key(|&i| -(i as i32));
-    Ok(circuit_size.iter().take(3).map(|&i| i as u32).product::<u32>().to_string())
-}
+    fn lint(&self, input: &str) -> Vec<Error> {
+        let mut problems = lint::whitespace(input);
+        problems.extend(lint::numbers::<i32>(input));
+        problems
+    }
 
-pub fn run_part_2(actual: bool) -> Result<String, ErrorMsg> {
-    let coords = read_coords(actual)?;
-    let connections = all_connections_sorted(&coords);
+    fn part_1(&self, coords: &Self::Input, params: &Params) -> Result<String, Error> {
+        let count = params.get::<usize>("count")?;
+        let connections = all_connections_sorted(coords);
 
-    let mut circuits = vec![-1i16; coords.len()];
-    let mut circuit_size: Vec<u16> = vec![];
+        let mut circuits = UnionFind::new(coords.len());
+        for connection in connections.iter().take(count) {
+            circuits.union(connection.from, connection.to);
+        }
 
-    for connection in connections.iter() {
-        combine_circuits(connection, &mut circuits, &mut circuit_size);
+        Ok(circuits.largest_components(3).product::<usize>().to_string())
+    }
+
+    fn part_2(&self, coords: &Self::Input, _: &Params) -> Result<String, Error> {
+        let connections = all_connections_sorted(coords);
 
-        if circuit_size.len() == 1 && circuit_size[0] == coords.len() as u16 {
-            return Ok((coords[connection.from as usize].x as u32 * coords[connection.to as usize].x as u32).to_string())
+        let mut circuits = UnionFind::new(coords.len());
+        for connection in connections.iter() {
+            if circuits.union(connection.from, connection.to) && circuits.component_count() == 1 {
+                return Ok((coords[connection.from].x * coords[connection.to].x).to_string())
+            }
         }
-    }
 
-    Err(ErrorMsg{wrapped: "End reached without total connection".to_string() })
-}
+        Err(Error::NoSolution("End reached without total connection".to_string()))
+    }
+}
\ No newline at end of file
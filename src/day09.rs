@@ -1,143 +1,102 @@
-﻿use std::cmp::{max, min};
-use std::str::FromStr;
+﻿use crate::solution::{Params, Solution};
 use crate::utils;
-use crate::utils::ErrorMsg;
+use crate::error::Error;
+use crate::geom::{Aabb, Dir, Point2};
 
-static SAMPLE: &str = "inputs/day09_sample.txt";
-static ACTUAL: &str = "inputs/day09.txt";
-
-struct Pos {
-    x: u32,
-    y: u32
-}
-
-struct Rect {
-    min: Pos,
-    max: Pos
-}
-
-#[derive(PartialEq)]
-enum Dir {
-    Up, Right, Down, Left
-}
-
-struct Line {
-    dir: Dir,
-    fixed: u32,
-    from: u32,
-    to: u32
+/// A straight edge of the loop, walked from one red tile to the next.
+pub struct Line {
+    pub dir: Dir,
+    /// The coordinate that stays the same along the line.
+    pub fixed: u64,
+    pub from: u64,
+    pub to: u64
 }
 
-impl Pos {
-
-    fn rect_with(&self, other: &Self) -> Rect {
-        Rect {
-            min: Pos {x: min(self.x, other.x), y: min(self.y, other.y)},
-            max: Pos {x: max(self.x, other.x), y: max(self.y, other.y)}
-        }
-    }
-
-    fn line_to(&self, other: &Self) -> Line {
-        if self.x == other.x {
-            if self.y < other.y {
-                Line { dir: Dir::Down, from: self.y, to: other.y, fixed: self.x}
+impl Line {
+    pub fn between(a: &Point2<u64>, b: &Point2<u64>) -> Line {
+        if a.x == b.x {
+            if a.y < b.y {
+                Line { dir: Dir::Down, from: a.y, to: b.y, fixed: a.x}
             } else {
-                Line { dir: Dir::Up, from: other.y, to: self.y, fixed: self.x}
+                Line { dir: Dir::Up, from: b.y, to: a.y, fixed: a.x}
             }
         } else {
-            if self.x < other.x {
-                Line { dir: Dir::Right, from: self.x, to: other.x, fixed: self.y}
+            if a.x < b.x {
+                Line { dir: Dir::Right, from: a.x, to: b.x, fixed: a.y}
             } else {
-                Line { dir: Dir::Left, from: other.x, to: self.x, fixed: self.y}
+                Line { dir: Dir::Left, from: b.x, to: a.x, fixed: a.y}
             }
         }
     }
-}
 
-impl Rect {
-    fn intersects(&self, line: &Line) -> bool {
-        if line.dir == Dir::Up || line.dir == Dir::Down {
-            if self.min.x > line.fixed || self.max.x < line.fixed || self.min.y >= line.to || self.max.y <= line.from {
+    /// Whether the line cuts into `rect`, or runs along an edge of it on the outside of the loop.
+    pub fn intersects(&self, rect: &Aabb<u64>) -> bool {
+        if self.dir.is_vertical() {
+            if rect.min.x > self.fixed || rect.max.x < self.fixed || rect.min.y >= self.to || rect.max.y <= self.from {
                 false
-            } else if self.min.x == line.fixed {
-                line.dir == Dir::Down
-            } else if self.max.x == line.fixed {
-                line.dir == Dir::Up
+            } else if rect.min.x == self.fixed {
+                self.dir == Dir::Down
+            } else if rect.max.x == self.fixed {
+                self.dir == Dir::Up
             } else {
                 true
             }
         } else {
-            if self.min.y > line.fixed || self.max.y < line.fixed || self.min.x >= line.to || self.max.x <= line.from {
+            if rect.min.y > self.fixed || rect.max.y < self.fixed || rect.min.x >= self.to || rect.max.x <= self.from {
                 false
-            } else if self.min.y == line.fixed {
-                line.dir == Dir::Left
-            } else if self.max.y == line.fixed {
-                line.dir == Dir::Right
+            } else if rect.min.y == self.fixed {
+                self.dir == Dir::Left
+            } else if rect.max.y == self.fixed {
+                self.dir == Dir::Right
             } else {
                 true
             }
         }
     }
-
-    fn size(&self) -> u64 {
-        let dx = (self.max.x - self.min.x + 1) as u64;
-        let dy = (self.max.y - self.min.y + 1) as u64;
-        dx * dy
-    }
 }
 
-impl FromStr for Pos {
-    type Err = ErrorMsg;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let vals = s.trim().split(',').collect::<Vec<&str>>();
-        if vals.len() == 2 {
-            Ok(Pos {x: vals[0].parse()?, y: vals[1].parse()?})
-        } else {
-            Err(ErrorMsg{wrapped: format!("Contains no single comma: {}", s)})
-        }
-    }
-}
+pub struct Day09;
 
-fn read_positions(actual: bool) -> Result<Vec<Pos>, ErrorMsg> {
-    let file = utils::read_file(if actual {ACTUAL} else {SAMPLE})?;
-    file.trim()
-        .split('\n')
-        .map(|l| l.parse::<Pos>())
-        .collect::<Result<Vec<Pos>, ErrorMsg>>()
-}
+impl Solution for Day09 {
+    type Input = Vec<Point2<u64>>;
+
+    fn day(&self) -> u8 { 9 }
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        utils::parse_lines(input)
+    }
 
-pub fn run_part_1(actual: bool) -> Result<String, ErrorMsg> {
-    let positions = read_positions(actual)?;
-    let mut max_rect = 0;
-    for i in 0..(positions.len() - 1) {
-        for j in (i + 1)..positions.len() {
-            let rect = positions[i].rect_with(&positions[j]);
-            let s = rect.size();
-            if s > max_rect {
-                max_rect = s;
+    fn part_1(&self, positions: &Self::Input, _: &Params) -> Result<String, Error> {
+        let mut max_rect = 0;
+        for i in 0..(positions.len() - 1) {
+            for j in (i + 1)..positions.len() {
+                let s = Aabb::from_corners(&positions[i], &positions[j]).area();
+                if s > max_rect {
+                    max_rect = s;
+                }
             }
         }
+        Ok(format!("{:?}", max_rect))
     }
-    Ok(format!("{:?}", max_rect))
-}
 
-pub fn run_part_2(actual: bool) -> Result<String, ErrorMsg> {
-    let positions = read_positions(actual)?;
-    let lines = (0..positions.len()).map(|i|
-        positions[i].line_to(&positions[(i+1) % positions.len()])
-    ).collect::<Vec<Line>>();
-    let mut max_rect = 0;
-    for i in 0..(positions.len() - 1) {
-        for j in (i + 1)..positions.len() {
-            let rect = positions[i].rect_with(&positions[j]);
-            if lines.iter().any(|l| rect.intersects(l)) {
-                continue;
-            }
-            let s = rect.size();
-            if s > max_rect {
-                max_rect = s;
+    fn part_2(&self, positions: &Self::Input, _: &Params) -> Result<String, Error> {
+        let lines = (0..positions.len()).map(|i|
+            Line::between(&positions[i], &positions[(i+1) % positions.len()])
+        ).collect::<Vec<Line>>();
+        let mut max_rect = 0;
+        for i in 0..(positions.len() - 1) {
+            utils::check_cancelled()?;
+            for j in (i + 1)..positions.len() {
+                let rect = Aabb::from_corners(&positions[i], &positions[j]);
+                if lines.iter().any(|l| l.intersects(&rect)) {
+                    continue;
+                }
+                let s = rect.area();
+                if s > max_rect {
+                    max_rect = s;
+                }
             }
         }
+        Ok(format!("{:?}", max_rect))
     }
-    Ok(format!("{:?}", max_rect))
 }
\ No newline at end of file
@@ -1,59 +1,76 @@
-﻿use std::cmp::{min, Ordering};
-use std::collections::{BinaryHeap, HashMap, HashSet};
-use std::num::ParseIntError;
+﻿use std::cmp::min;
+use std::collections::HashMap;
 use std::ops;
 use std::str::FromStr;
 use lazy_static::lazy_static;
 use regex::Regex;
-use substring::Substring;
+use crate::search;
+use crate::search::Visited;
+use crate::solution::{Params, Solution};
 use crate::utils;
-use crate::utils::ErrorMsg;
+use crate::error::Error;
+use crate::lint;
 use memoize::memoize;
 use itertools::Itertools;
 
-static SAMPLE: &str = "inputs/day10_sample.txt";
-static ACTUAL: &str = "inputs/day10.txt";
-
 #[derive(Clone)]
-struct Machine {
-    light_diagram: Vec<bool>,
-    light_diagram_mask: u16,
-    button_wiring: Vec<Joltage>,
-    button_wiring_masks: Vec<u16>,
-    joltage: Joltage
+pub struct Machine {
+    pub light_diagram: Vec<bool>,
+    pub light_diagram_mask: u16,
+    pub button_wiring: Vec<Joltage>,
+    pub button_wiring_masks: Vec<u16>,
+    pub joltage: Joltage
 }
 
 lazy_static! {
     static ref MACHINE_REGEX: Regex = Regex::new(r"\[(.*)\] (.*) \{(.*)\}").unwrap();
 }
 impl FromStr for Machine {
-    type Err = ErrorMsg;
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let captures = MACHINE_REGEX.captures(s).ok_or(ErrorMsg{wrapped:format!("Failed to capture machine regex in {s}")})?;
-        let lights = captures[1].chars().map(|s| match s {
+        let captures = MACHINE_REGEX.captures(s).ok_or(Error::Parse(format!("Failed to capture machine regex in {s}")))?;
+        let group = |i: usize| captures.get(i).map_or((0, ""), |m| (m.start(), m.as_str()));
+        let (lights_start, lights_str) = group(1);
+        let lights = lights_str.char_indices().map(|(i, c)| match c {
             '#' => Ok(true),
             '.' => Ok(false),
-            _ => Err(ErrorMsg{wrapped: format!("Unrecognized light {}", s)})
-        }).collect::<Result<Vec<bool>, ErrorMsg>>()?;
+            _ => Err(Error::Parse(format!("Unrecognized light {}", c))
+                .in_span(s, lights_start + i..lights_start + i + c.len_utf8()))
+        }).collect::<Result<Vec<bool>, Error>>()?;
+        if lights.len() > u16::BITS as usize {
+            return Err(Error::Parse(format!("{} lights do not fit in the 16-bit masks", lights.len()))
+                .in_span(s, lights_start..lights_start + lights_str.len()));
+        }
         let mut lights_mask = 0;
         for (i, activate) in lights.iter().enumerate() {
             if *activate {
                 lights_mask |= 1 << i
             }
         }
-        let buttons = captures[2].split(' ').map(|s|
-            s.substring(1, s.len() - 1).split(',').map(|s2| s2.parse::<u16>()).collect::<Result<Vec<u16>, ParseIntError>>())
-            .collect::<Result<Vec<Vec<u16>>, ParseIntError>>()?;
+        let (buttons_start, buttons_str) = group(2);
+        let mut button_start = buttons_start;
+        let buttons = buttons_str.split(' ').map(|b| {
+            let span = button_start..button_start + b.len();
+            button_start = span.end + 1;
+            match b.strip_prefix('(').and_then(|b| b.strip_suffix(')')) {
+                Some(list) => parse_numbers(s, list, span.start + 1, Some(lights.len())),
+                None => Err(Error::Parse(format!("Expected a button like (0,2), found '{}'", b)).in_span(s, span))
+            }
+        }).collect::<Result<Vec<Vec<u16>>, Error>>()?;
         let button_masks = buttons.iter().map(|b| {
             let mut mask = 0;
             for &i in b {
-                mask |= 1 << (i as u16)
+                mask |= 1 << i
             }
             mask
         }).collect::<Vec<u16>>();
-        let joltage = captures[3].split(',').map(|s| s.parse::<u16>())
-            .collect::<Result<Vec<u16>, ParseIntError>>()?;
-        let mut button_wiring = buttons.iter().map(|b| {
+        let (joltage_start, joltage_str) = group(3);
+        let joltage = parse_numbers(s, joltage_str, joltage_start, None)?;
+        if joltage.len() != lights.len() {
+            return Err(Error::Parse(format!("Expected {} joltage levels, one per light, found {}", lights.len(), joltage.len()))
+                .in_span(s, joltage_start..joltage_start + joltage_str.len()));
+        }
+        let button_wiring = buttons.iter().map(|b| {
             let mut values = vec![0; lights.len()];
             for bb in b {
                 values[*bb as usize] = 1;
@@ -71,43 +88,20 @@ impl FromStr for Machine {
     }
 }
 
-fn read_input(actual: bool) -> Result<Vec<Machine>, ErrorMsg> {
-    let file = utils::read_file(if actual {ACTUAL} else {SAMPLE})?;
-    file.trim()
-        .split('\n')
-        .map(|l| l.parse::<Machine>())
-        .collect::<Result<Vec<Machine>, ErrorMsg>>()
-}
-
-fn subsets(total_len: usize, subset_size: usize) -> Vec<usize> {
-    if subset_size == 0 {
-        return vec![0];
-    }
-    if total_len == 0 {
-        return vec![];
-    } else if subset_size == 0 {
-        return vec![0];
-    }
-    let mut ret = subsets(total_len - 1, subset_size);
-    for i  in subsets(total_len - 1, subset_size - 1) {
-        ret.push(i | (1 << (total_len - 1)))
-    }
-    ret
-}
-
-fn can_satisfy_lights_in(machine: &Machine, presses: usize) -> bool {
-    for subset in subsets(machine.button_wiring.len(), presses) {
-        let mut lights = 0;
-        for (i, b) in machine.button_wiring_masks.iter().enumerate() {
-            if (1 << i) & subset != 0 {
-                lights ^= b;
-            }
-        }
-        if lights == machine.light_diagram_mask {
-            return true;
+/// Parses the comma-separated `list`, which starts at byte `start` of the machine description `s`.
+/// With `lights`, the numbers index the lights and must be below that count.
+fn parse_numbers(s: &str, list: &str, start: usize, lights: Option<usize>) -> Result<Vec<u16>, Error> {
+    let mut number_start = start;
+    list.split(',').map(|n| {
+        let span = number_start..number_start + n.len();
+        number_start = span.end + 1;
+        let number = n.parse::<u16>().map_err(|e| Error::from(e).in_span(s, span.clone()))?;
+        match lights {
+            Some(count) if number as usize >= count =>
+                Err(Error::Parse(format!("There is no light {}, the machine has {}", number, count)).in_span(s, span)),
+            _ => Ok(number)
         }
-    }
-    false
+    }).collect()
 }
 
 // fn min_button_presses_only(machine: &Machine, current_joltage: &Vec<u16>, i: usize) -> Option<u32> {
@@ -196,10 +190,11 @@ fn distributions(max_rem: u8, to_distribute: u8, receivers: usize) -> Vec<Vec<u8
     }
 }
 
-fn min_button_presses(button_bundles: &Vec<Vec<&Joltage>>, remaining_joltage: Joltage, bundle_index: u8, mut cache: &mut HashMap<(Joltage, u8), Option<u32>>) -> Option<u32> {
+fn min_button_presses(button_bundles: &Vec<Vec<&Joltage>>, remaining_joltage: Joltage, bundle_index: u8, cache: &mut HashMap<(Joltage, u8), Option<u32>>) -> Result<Option<u32>, Error> {
+    utils::check_cancelled()?;
     let t = (remaining_joltage.clone(), bundle_index);
     if let Some(res) = cache.get(&t) {
-        return *res;
+        return Ok(*res);
     }
     let bundle = &button_bundles[bundle_index as usize];
     let max_rem = *remaining_joltage.values.iter().max().unwrap() as u32;
@@ -211,76 +206,37 @@ fn min_button_presses(button_bundles: &Vec<Vec<&Joltage>>, remaining_joltage: Jo
             let mut new_joltage = remaining_joltage.clone();
             for j in 0..bundle.len() {
                 for _ in 0..dist[j] {
-                    if new_joltage.sub(&bundle[j]) == None {
+                    if new_joltage.sub(bundle[j]).is_none() {
                         continue 'inner;
                     }
                 }
             }
             if new_joltage.is_zero() {
-                ret = Some(i as u32);
+                ret = Some(i);
                 break 'outer;
             }
             if bundle_index > 0 {
-                let local_res = min_button_presses(&button_bundles, new_joltage, bundle_index - 1, &mut cache);
+                let local_res = min_button_presses(button_bundles, new_joltage, bundle_index - 1, cache)?;
                 if let Some(mut lr) = local_res {
                     lr += i;
-                    if ret == None || ret.unwrap() > lr {
+                    if ret.is_none() || ret.unwrap() > lr {
                         ret = Some(lr);
                     }
                 }
             }
         }
-        if ret != None {
+        if ret.is_some() {
             break;
         }
     }
     cache.insert((remaining_joltage.clone(), bundle_index), ret);
-    ret
-}
-
-pub fn run_part_1(actual: bool) -> Result<String, ErrorMsg> {
-    let machines = read_input(actual)?;
-    let mut result = 0;
-    for machine in machines {
-        for i in 1..machine.button_wiring_masks.len() {
-            if can_satisfy_lights_in(&machine, i) {
-                result += i;
-                break;
-            }
-        }
-    }
-    Ok(result.to_string())
+    Ok(ret)
 }
 
 #[derive(Eq, PartialEq, Clone, Hash)]
 #[derive(Debug)]
-struct Joltage {
-    values: Vec<u16>
-}
-
-#[derive(Eq, PartialEq)]
-#[derive(Debug)]
-struct JoltageEntry {
-    joltage_remaining: Joltage,
-    presses_so_far: u32
-}
-
-impl Ord for JoltageEntry {
-    fn cmp(&self, other: &Self) -> Ordering {
-        (other.presses_so_far).cmp(&(self.presses_so_far))
-    }
-}
-
-impl PartialOrd for JoltageEntry {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl JoltageEntry {
-    fn from(joltage_remaining: Joltage, presses_so_far: u32) -> JoltageEntry{
-        JoltageEntry{joltage_remaining, presses_so_far}
-    }
+pub struct Joltage {
+    pub values: Vec<u16>
 }
 
 impl ops::Sub for &Joltage {
@@ -306,7 +262,7 @@ impl ops::Add for &Joltage {
 }
 
 impl Joltage {
-    fn is_zero(&self) -> bool {
+    pub fn is_zero(&self) -> bool {
         for v in &self.values {
             if *v != 0 {
                 return false
@@ -314,12 +270,12 @@ impl Joltage {
         }
         true
     }
-    fn add(&mut self, rhs: &Self) {
+    pub fn add(&mut self, rhs: &Self) {
         for i in 0..self.values.len() {
             self.values[i] += rhs.values[i]
         }
     }
-    fn sub(&mut self, rhs: &Self) -> Option<()> {
+    pub fn sub(&mut self, rhs: &Self) -> Option<()> {
         for i in 0..self.values.len() {
             self.values[i] = self.values[i].checked_sub(rhs.values[i])?
         }
@@ -327,47 +283,51 @@ impl Joltage {
     }
 }
 
-pub fn run_part_2(actual: bool) -> Result<String, ErrorMsg> {
-    let machines = read_input(actual)?;
-    let mut result = 0u32;
-    for machine in machines {
-        // let mut seen = HashSet::new();
-        // let mut heap = BinaryHeap::new();
-        // heap.push(JoltageEntry::from(machine.joltage, 0));
-        // while let Some(entry) = heap.pop() {
-        //     if seen.contains(&entry.joltage_remaining) {
-        //         continue;
-        //     }
-        //     if entry.joltage_remaining.is_zero() {
-        //         result += entry.presses_so_far;
-        //         println!("{}", entry.presses_so_far);
-        //         seen.insert(entry.joltage_remaining);
-        //         break;
-        //     }
-        //     let new_presses = entry.presses_so_far + 1;
-        //     for b in &machine.button_wiring {
-        //         if let Some(new_joltage) = &entry.joltage_remaining - b {
-        //             if !seen.contains(&new_joltage) {
-        //                 let new_entry = JoltageEntry::from(new_joltage, new_presses);
-        //                 heap.push(new_entry)
-        //             }
-        //         }
-        //     }
-        //     seen.insert(entry.joltage_remaining);
-        // }
-        // if !seen.contains(&Joltage{values: vec![0; machine.light_diagram.len()]}) {
-        //     println!("AAAAA")
-        // }
-        let buttons = machine.button_wiring.iter()
-            .sorted_by_key(|bw| bw.values.iter().sum::<u16>())
-            .chunk_by(|bw| bw.values.iter().sum::<u16>())
-            .into_iter()
-            .map(|g| g.1.collect::<Vec<&Joltage>>())
-            .collect::<Vec<Vec<&Joltage>>>();
-        let min_o = min_button_presses(&buttons, machine.joltage, buttons.len() as u8 - 1, &mut HashMap::new())
-            .ok_or_else(|| ErrorMsg{wrapped: format!("Failed to reach joltage")})?;
-        println!("{}", min_o);
-        result += min_o;
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<Machine>;
+
+    fn day(&self) -> u8 { 10 }
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        utils::parse_lines(input)
+    }
+
+    fn lint(&self, input: &str) -> Vec<Error> {
+        let mut problems = lint::whitespace(input);
+        problems.extend(lint::numbers::<u16>(input));
+        problems
+    }
+
+    fn part_1(&self, machines: &Self::Input, _: &Params) -> Result<String, Error> {
+        let mut result = 0;
+        for machine in machines {
+            // Pressing a button twice undoes it, so the lights alone are the state.
+            let presses = search::bfs(
+                0u16,
+                |&lights| machine.button_wiring_masks.iter().map(move |mask| lights ^ mask),
+                |&lights| lights == machine.light_diagram_mask,
+                Visited::Remember
+            )?.ok_or_else(|| Error::NoSolution("Failed to reach light diagram".to_string()))?;
+            result += presses.cost;
+        }
+        Ok(result.to_string())
+    }
+
+    fn part_2(&self, machines: &Self::Input, _: &Params) -> Result<String, Error> {
+        let mut result = 0u32;
+        for machine in machines {
+            let buttons = machine.button_wiring.iter()
+                .sorted_by_key(|bw| bw.values.iter().sum::<u16>())
+                .chunk_by(|bw| bw.values.iter().sum::<u16>())
+                .into_iter()
+                .map(|g| g.1.collect::<Vec<&Joltage>>())
+                .collect::<Vec<Vec<&Joltage>>>();
+            let min_o = min_button_presses(&buttons, machine.joltage.clone(), buttons.len() as u8 - 1, &mut HashMap::new())?
+                .ok_or_else(|| Error::NoSolution("Failed to reach joltage".to_string()))?;
+            result += min_o;
+        }
+        Ok(result.to_string())
     }
-    Ok(result.to_string())
 }
\ No newline at end of file
@@ -0,0 +1,261 @@
+use std::error;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::num::ParseIntError;
+use std::ops::Range;
+
+/// Everything that can go wrong, grouped by what the user can do about it. [`Error::exit_code`] tells the categories apart.
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    ParseInt(ParseIntError),
+    Regex(regex::Error),
+    /// The puzzle input does not have the expected shape.
+    Parse(String),
+    /// The input is fine, but has no answer.
+    NoSolution(String),
+    /// The command line, a parameter or the setup is wrong.
+    Usage(String),
+    /// A file the tool keeps, like `answers.toml`, could not be read or written in its format.
+    Store { message: String, source: Box<dyn error::Error + Send + Sync> },
+    /// The puzzle site could not be reached or answered with an error.
+    Network { message: String, source: Option<Box<dyn error::Error + Send + Sync>> },
+    /// The run was stopped from outside, e.g. because it timed out.
+    Cancelled,
+    /// Anything else that keeps a command from succeeding, e.g. failed checks.
+    Failed(String),
+    /// `source` happened at `location` in the input.
+    Located { source: Box<Error>, location: Location }
+}
+
+/// Where in the input an error happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// The input file, once it is known.
+    pub path: Option<String>,
+    /// 1-based. `None` while the error is only known relative to the text its parser was given.
+    pub line: Option<usize>,
+    /// Byte range of the offending part of `text`.
+    pub columns: Range<usize>,
+    /// The line the error is in, or the text its parser was given.
+    pub text: String
+}
+
+impl Location {
+    /// 1-based, in characters.
+    pub fn column(&self) -> usize {
+        self.text.get(..self.columns.start.min(self.text.len())).map_or(0, |prefix| prefix.chars().count()) + 1
+    }
+}
+
+impl Error {
+    pub fn store(message: impl Into<String>, source: impl error::Error + Send + Sync + 'static) -> Error {
+        Error::Store { message: message.into(), source: Box::new(source) }
+    }
+
+    pub fn network(message: impl Into<String>) -> Error {
+        Error::Network { message: message.into(), source: None }
+    }
+
+    /// Marks `columns` of `text`, the string a parser was given, as the cause.
+    pub fn in_span(self, text: &str, columns: Range<usize>) -> Error {
+        Error::Located {
+            source: Box::new(self),
+            location: Location { path: None, line: None, columns, text: text.to_string() }
+        }
+    }
+
+    /// Places the error in `line`, the `number`th (1-based) line of the input, of which its parser was given `fragment`.
+    /// Keeps the span within the fragment if the parser marked one.
+    pub fn at_line(self, number: usize, line: &str, fragment: Range<usize>) -> Error {
+        let text = line.to_string();
+        match self {
+            Error::Located { source, location } if location.line.is_none() => {
+                let columns = fragment.start + location.columns.start..fragment.start + location.columns.end;
+                Error::Located { source, location: Location { line: Some(number), columns, text, ..location } }
+            },
+            located @ Error::Located { .. } => located,
+            err => Error::Located {
+                source: Box::new(err),
+                location: Location { path: None, line: Some(number), columns: fragment, text }
+            }
+        }
+    }
+
+    /// Records which file the input came from, for errors that have a location.
+    pub fn in_file(self, path: &str) -> Error {
+        match self {
+            Error::Located { source, location } if location.path.is_none() =>
+                Error::Located { source, location: Location { path: Some(path.to_string()), ..location } },
+            err => err
+        }
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Error::Located { location, .. } => Some(location),
+            _ => None
+        }
+    }
+
+    /// A rustc-style report: the message and, if the error has a location, the input line with the cause underlined.
+    pub fn report(&self) -> String {
+        let (source, location) = match self {
+            Error::Located { source, location } => (source, location),
+            err => return format!("error: {}", err)
+        };
+        let line = location.line.map_or(String::new(), |l| l.to_string());
+        let path = location.path.as_deref().unwrap_or("<input>");
+        let position = match location.line {
+            Some(line) => format!("{}:{}:{}", path, line, location.column()),
+            None => path.to_string()
+        };
+        let gutter = " ".repeat(line.len());
+        let start = location.columns.start.min(location.text.len());
+        let end = location.columns.end.clamp(start, location.text.len());
+        // Keeps tabs so the carets line up with the text above them.
+        let indent = location.text.get(..start).unwrap_or_default().chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let carets = "^".repeat(location.text.get(start..end).map_or(0, |s| s.chars().count()).max(1));
+        let code = format!("{} | {}", line, location.text.trim_end());
+        format!("error: {}\n{}--> {}\n{} |\n{}\n{} | {}{}",
+            source,
+            gutter, position,
+            gutter,
+            code.trim_end(),
+            gutter, indent, carets)
+    }
+
+    /// The process exit code for this kind of error:
+    /// 1 failed, 2 usage, 3 files, 4 invalid input, 5 no solution, 6 network, 7 cancelled.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Failed(_) | Error::Regex(_) => 1,
+            Error::Usage(_) => 2,
+            Error::Io(_) | Error::Store { .. } => 3,
+            Error::Parse(_) | Error::ParseInt(_) => 4,
+            Error::NoSolution(_) => 5,
+            Error::Network { .. } => 6,
+            Error::Cancelled => 7,
+            Error::Located { source, .. } => source.exit_code()
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "IO error: {}", err),
+            Error::ParseInt(err) => write!(f, "Invalid number: {}", err),
+            Error::Regex(err) => write!(f, "Failed to compile regex: {}", err),
+            Error::Parse(message) | Error::NoSolution(message) | Error::Usage(message) | Error::Failed(message) =>
+                write!(f, "{}", message),
+            Error::Store { message, source } => write!(f, "{}: {}", message, source),
+            Error::Network { message, source: Some(source) } => write!(f, "{}: {}", message, source),
+            Error::Network { message, source: None } => write!(f, "{}", message),
+            Error::Cancelled => write!(f, "Cancelled"),
+            Error::Located { source, location } => match (&location.path, location.line) {
+                (Some(path), Some(line)) => write!(f, "{} ({}:{}:{})", source, path, line, location.column()),
+                (None, Some(line)) => write!(f, "{} (line {}:{})", source, line, location.column()),
+                _ => write!(f, "{}", source)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::ParseInt(err) => Some(err),
+            Error::Regex(err) => Some(err),
+            Error::Store { source, .. } => Some(source.as_ref()),
+            Error::Network { source, .. } => source.as_deref().map(|s| s as &(dyn error::Error + 'static)),
+            Error::Located { source, .. } => Some(source.as_ref()),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(err: ParseIntError) -> Self {
+        Error::ParseInt(err)
+    }
+}
+
+impl From<regex::Error> for Error {
+    fn from(err: regex::Error) -> Self {
+        Error::Regex(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use super::*;
+
+    #[test]
+    fn every_category_has_its_own_exit_code() {
+        let cases = [
+            (Error::Failed("checks failed".to_string()), 1),
+            (Error::Regex(regex::Error::Syntax("unclosed group".to_string())), 1),
+            (Error::Usage("bad flag".to_string()), 2),
+            (Error::Io(io::Error::new(io::ErrorKind::NotFound, "missing")), 3),
+            (Error::store("bad file", io::Error::other("broken")), 3),
+            (Error::Parse("bad input".to_string()), 4),
+            (Error::ParseInt("x".parse::<u8>().unwrap_err()), 4),
+            (Error::NoSolution("nothing".to_string()), 5),
+            (Error::network("offline"), 6),
+            (Error::Cancelled, 7),
+            (Error::Parse("bad line".to_string()).at_line(3, "abc", 0..1), 4),
+            (Error::Cancelled.in_span("abc", 1..2).in_file("inputs/day01.txt"), 7)
+        ];
+        for (err, code) in &cases {
+            assert_eq!(err.exit_code(), *code, "{:?}", err);
+        }
+        let codes = cases.iter().map(|(err, _)| err.exit_code()).collect::<BTreeSet<i32>>();
+        assert_eq!(codes.into_iter().collect::<Vec<i32>>(), [1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn report_underlines_the_cause() {
+        let err = Error::Parse("Bad number".to_string()).at_line(7, "12,x4", 3..5).in_file("inputs/day01.txt");
+        assert_eq!(err.report(), "error: Bad number\n --> inputs/day01.txt:7:4\n  |\n7 | 12,x4\n  |    ^^");
+    }
+
+    #[test]
+    fn report_at_column_zero() {
+        let whole = Error::Parse("Bad line".to_string()).at_line(3, "abc", 0..3);
+        assert_eq!(whole.report(), "error: Bad line\n --> <input>:3:1\n  |\n3 | abc\n  | ^^^");
+        let empty = Error::Parse("Bad line".to_string()).at_line(3, "abc", 0..0);
+        assert_eq!(empty.report(), "error: Bad line\n --> <input>:3:1\n  |\n3 | abc\n  | ^");
+    }
+
+    #[test]
+    fn report_keeps_tabs_in_line() {
+        let err = Error::Parse("Tab character".to_string()).at_line(12, "\tx\ty", 2..3);
+        assert_eq!(err.report(), "error: Tab character\n  --> <input>:12:3\n   |\n12 | \tx\ty\n   | \t ^");
+    }
+
+    #[test]
+    fn report_at_end_of_line() {
+        let err = Error::Parse("Expected more".to_string()).at_line(2, "1,2,", 4..4);
+        assert_eq!(err.report(), "error: Expected more\n --> <input>:2:5\n  |\n2 | 1,2,\n  |     ^");
+        let past = Error::Parse("Expected more".to_string()).at_line(2, "1,2", 5..9);
+        assert_eq!(past.report(), "error: Expected more\n --> <input>:2:4\n  |\n2 | 1,2\n  |    ^");
+    }
+
+    #[test]
+    fn report_past_the_last_line() {
+        let err = Error::Parse("Missing the second section".to_string()).at_line(100, "", 0..0);
+        assert_eq!(err.report(), "error: Missing the second section\n   --> <input>:100:1\n    |\n100 |\n    | ^");
+        assert_eq!(Error::Cancelled.report(), "error: Cancelled");
+    }
+}
@@ -0,0 +1,116 @@
+﻿use std::path::{Path, PathBuf};
+use std::{env, fs};
+use crate::http;
+use crate::http::HttpClient;
+use crate::input;
+use crate::utils;
+use crate::error::Error;
+
+pub static YEAR: u16 = 2025;
+static SESSION_FILE: &str = ".aoc-session";
+
+/// Reads the session cookie from `AOC_SESSION`, `./.aoc-session` or `~/.config/aoc/session`, in that order.
+pub fn session_token() -> Result<String, Error> {
+    if let Ok(token) = env::var("AOC_SESSION") && !token.trim().is_empty() {
+        return Ok(token.trim().to_string());
+    }
+    let mut candidates = vec![PathBuf::from(SESSION_FILE)];
+    if let Ok(home) = env::var("HOME") {
+        candidates.push(Path::new(&home).join(".config").join("aoc").join("session"));
+    }
+    for path in candidates {
+        if path.exists() {
+            let token = utils::read_file(&path.to_string_lossy())?;
+            return Ok(token.trim().to_string());
+        }
+    }
+    Err(Error::Usage("No session token: set AOC_SESSION or write it to .aoc-session".to_string()))
+}
+
+pub enum Fetched {
+    Cached(String),
+    Downloaded(String)
+}
+
+/// Downloads the input of `day` to its actual input path, unless that file already has content.
+/// An empty file, like the placeholder `aoc new` creates, is replaced.
+pub fn fetch_input(client: &dyn HttpClient, base_url: &str, token: &str, day: u8) -> Result<Fetched, Error> {
+    fetch_to(client, base_url, token, day, input::actual_path(day))
+}
+
+fn fetch_to(client: &dyn HttpClient, base_url: &str, token: &str, day: u8, path: String) -> Result<Fetched, Error> {
+    if fs::metadata(&path).is_ok_and(|m| m.len() > 0) {
+        return Ok(Fetched::Cached(path));
+    }
+    let url = format!("{}/{}/day/{}/input", base_url, YEAR, day);
+    let cookie = format!("session={}", token);
+    let response = client.get(&url, &http::session_headers(&cookie))?;
+    if response.status != 200 {
+        return Err(Error::network(format!("Fetching {} failed with status {}: {}", url, response.status, response.body.trim())));
+    }
+    if let Some(dir) = Path::new(&path).parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, response.body)?;
+    Ok(Fetched::Downloaded(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process;
+    use crate::http::fake::FakeClient;
+    use crate::http::USER_AGENT;
+    use super::*;
+
+    /// A path in a fresh temporary directory, so tests neither touch `inputs/` nor each other.
+    fn temp_path(name: &str) -> String {
+        let dir = env::temp_dir().join(format!("aoc-fetch-{}-{}", process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("day01.txt").to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn downloads_with_session_and_user_agent() {
+        let client = FakeClient::new(200, "1\n2\n");
+        let path = temp_path("download");
+        let fetched = fetch_to(&client, "http://aoc.test", "secret", 1, path.clone()).unwrap();
+        assert!(matches!(fetched, Fetched::Downloaded(p) if p == path));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1\n2\n");
+
+        let requests = client.requests.borrow();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].url, format!("http://aoc.test/{}/day/1/input", YEAR));
+        assert_eq!(requests[0].header("Cookie"), Some("session=secret"));
+        assert_eq!(requests[0].header("User-Agent"), Some(USER_AGENT));
+        assert!(requests[0].form.is_empty());
+    }
+
+    #[test]
+    fn cached_input_is_not_downloaded_again() {
+        let client = FakeClient::new(200, "new");
+        let path = temp_path("cached");
+        fs::create_dir_all(Path::new(&path).parent().unwrap()).unwrap();
+        fs::write(&path, "old").unwrap();
+        assert!(matches!(fetch_to(&client, "http://aoc.test", "secret", 1, path.clone()).unwrap(), Fetched::Cached(_)));
+        assert!(client.requests.borrow().is_empty());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+    }
+
+    #[test]
+    fn empty_placeholder_is_replaced() {
+        let client = FakeClient::new(200, "new");
+        let path = temp_path("placeholder");
+        fs::create_dir_all(Path::new(&path).parent().unwrap()).unwrap();
+        fs::write(&path, "").unwrap();
+        assert!(matches!(fetch_to(&client, "http://aoc.test", "secret", 1, path.clone()).unwrap(), Fetched::Downloaded(_)));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+    }
+
+    #[test]
+    fn failed_download_writes_nothing() {
+        let client = FakeClient::new(400, "Please log in");
+        let path = temp_path("failed");
+        assert!(matches!(fetch_to(&client, "http://aoc.test", "secret", 1, path.clone()), Err(Error::Network { .. })));
+        assert!(!Path::new(&path).exists());
+    }
+}
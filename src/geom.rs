@@ -0,0 +1,380 @@
+use std::cmp::{max, min};
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+use crate::utils::num::Coord;
+use crate::error::Error;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T
+}
+
+impl<T: Coord> Point2<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Point2 { x, y }
+    }
+
+    pub fn manhattan(&self, other: &Self) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y)
+    }
+
+    pub fn chebyshev(&self, other: &Self) -> T {
+        max(self.x.distance(other.x), self.y.distance(other.y))
+    }
+
+    /// The squared straight-line distance, which keeps to integers and orders points the same way.
+    pub fn euclidean_sq(&self, other: &Self) -> T {
+        let (dx, dy) = (self.x.distance(other.x), self.y.distance(other.y));
+        dx * dx + dy * dy
+    }
+
+    /// Converts each coordinate, e.g. to a wider type before computing distances.
+    pub fn map<U>(&self, f: impl Fn(T) -> U) -> Point2<U> {
+        Point2 { x: f(self.x), y: f(self.y) }
+    }
+}
+
+impl<T: Coord> Point3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+
+    pub fn manhattan(&self, other: &Self) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y) + self.z.distance(other.z)
+    }
+
+    pub fn chebyshev(&self, other: &Self) -> T {
+        max(max(self.x.distance(other.x), self.y.distance(other.y)), self.z.distance(other.z))
+    }
+
+    /// The squared straight-line distance, which keeps to integers and orders points the same way.
+    pub fn euclidean_sq(&self, other: &Self) -> T {
+        let (dx, dy, dz) = (self.x.distance(other.x), self.y.distance(other.y), self.z.distance(other.z));
+        dx * dx + dy * dy + dz * dz
+    }
+
+    /// Converts each coordinate, e.g. to a wider type before computing distances.
+    pub fn map<U>(&self, f: impl Fn(T) -> U) -> Point3<U> {
+        Point3 { x: f(self.x), y: f(self.y), z: f(self.z) }
+    }
+}
+
+impl<T: Coord> Add for Point2<T> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Point2 { x: self.x + other.x, y: self.y + other.y }
+    }
+}
+
+impl<T: Coord> Sub for Point2<T> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Point2 { x: self.x - other.x, y: self.y - other.y }
+    }
+}
+
+impl<T: Coord> Mul<T> for Point2<T> {
+    type Output = Self;
+    fn mul(self, factor: T) -> Self {
+        Point2 { x: self.x * factor, y: self.y * factor }
+    }
+}
+
+impl<T: Coord + Neg<Output = T>> Neg for Point2<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Point2 { x: -self.x, y: -self.y }
+    }
+}
+
+impl<T: Coord> Add for Point3<T> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Point3 { x: self.x + other.x, y: self.y + other.y, z: self.z + other.z }
+    }
+}
+
+impl<T: Coord> Sub for Point3<T> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Point3 { x: self.x - other.x, y: self.y - other.y, z: self.z - other.z }
+    }
+}
+
+impl<T: Coord> Mul<T> for Point3<T> {
+    type Output = Self;
+    fn mul(self, factor: T) -> Self {
+        Point3 { x: self.x * factor, y: self.y * factor, z: self.z * factor }
+    }
+}
+
+impl<T: Coord + Neg<Output = T>> Neg for Point3<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Point3 { x: -self.x, y: -self.y, z: -self.z }
+    }
+}
+
+/// Parses `N` comma-separated numbers. Errors point at the number that failed.
+fn parse_coords<T, const N: usize>(s: &str) -> Result<[T; N], Error> where T: FromStr, T::Err: Into<Error> {
+    let pieces = s.split(',').collect::<Vec<&str>>();
+    if pieces.len() != N {
+        return Err(Error::Parse(format!("Expected {} comma-separated coordinates: {}", N, s.trim())));
+    }
+    let mut start = 0;
+    let mut coords = Vec::with_capacity(N);
+    for piece in pieces {
+        let offset = start + piece.len() - piece.trim_start().len();
+        let span = offset..offset + piece.trim().len();
+        coords.push(piece.trim().parse::<T>().map_err(|err| err.into().in_span(s, span))?);
+        start += piece.len() + 1;
+    }
+    Ok(coords.try_into().unwrap_or_else(|_| unreachable!()))
+}
+
+impl<T> FromStr for Point2<T> where T: FromStr, T::Err: Into<Error> {
+    type Err = Error;
+    /// Parses `x,y`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x, y] = parse_coords(s)?;
+        Ok(Point2 { x, y })
+    }
+}
+
+impl<T> FromStr for Point3<T> where T: FromStr, T::Err: Into<Error> {
+    type Err = Error;
+    /// Parses `x,y,z`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x, y, z] = parse_coords(s)?;
+        Ok(Point3 { x, y, z })
+    }
+}
+
+/// An axis-aligned box on a grid. Both corners are part of it, so the box from `(0, 0)` to `(1, 1)` covers four cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Aabb<T> {
+    pub min: Point2<T>,
+    pub max: Point2<T>
+}
+
+impl<T: Coord> Aabb<T> {
+    /// The smallest box containing both `a` and `b`, whichever corners they are.
+    pub fn from_corners(a: &Point2<T>, b: &Point2<T>) -> Self {
+        Aabb {
+            min: Point2 { x: min(a.x, b.x), y: min(a.y, b.y) },
+            max: Point2 { x: max(a.x, b.x), y: max(a.y, b.y) }
+        }
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::ONE
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::ONE
+    }
+
+    /// The number of cells in the box.
+    pub fn area(&self) -> T {
+        self.width() * self.height()
+    }
+
+    pub fn contains(&self, point: &Point2<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    /// The cells both boxes cover, if there are any.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let low = Point2 { x: max(self.min.x, other.min.x), y: max(self.min.y, other.min.y) };
+        let high = Point2 { x: min(self.max.x, other.max.x), y: min(self.max.y, other.max.y) };
+        (low.x <= high.x && low.y <= high.y).then_some(Aabb { min: low, max: high })
+    }
+
+    /// The smallest box that covers both boxes.
+    pub fn union(&self, other: &Self) -> Self {
+        Aabb {
+            min: Point2 { x: min(self.min.x, other.min.x), y: min(self.min.y, other.min.y) },
+            max: Point2 { x: max(self.max.x, other.max.x), y: max(self.max.y, other.max.y) }
+        }
+    }
+}
+
+/// An axis-aligned box in space, inclusive like [`Aabb`], so the box from `(0, 0, 0)` to `(1, 1, 1)` covers eight cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Aabb3<T> {
+    pub min: Point3<T>,
+    pub max: Point3<T>
+}
+
+impl<T: Coord> Aabb3<T> {
+    /// The smallest box containing both `a` and `b`, whichever corners they are.
+    pub fn from_corners(a: &Point3<T>, b: &Point3<T>) -> Self {
+        Aabb3 {
+            min: Point3 { x: min(a.x, b.x), y: min(a.y, b.y), z: min(a.z, b.z) },
+            max: Point3 { x: max(a.x, b.x), y: max(a.y, b.y), z: max(a.z, b.z) }
+        }
+    }
+
+    /// The number of cells in the box.
+    pub fn volume(&self) -> T {
+        (self.max.x - self.min.x + T::ONE) * (self.max.y - self.min.y + T::ONE) * (self.max.z - self.min.z + T::ONE)
+    }
+
+    pub fn contains(&self, point: &Point3<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x)
+            && (self.min.y..=self.max.y).contains(&point.y)
+            && (self.min.z..=self.max.z).contains(&point.z)
+    }
+
+    /// The cells both boxes cover, if there are any.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let low = Point3 { x: max(self.min.x, other.min.x), y: max(self.min.y, other.min.y), z: max(self.min.z, other.min.z) };
+        let high = Point3 { x: min(self.max.x, other.max.x), y: min(self.max.y, other.max.y), z: min(self.max.z, other.max.z) };
+        (low.x <= high.x && low.y <= high.y && low.z <= high.z).then_some(Aabb3 { min: low, max: high })
+    }
+
+    /// The smallest box that covers both boxes.
+    pub fn union(&self, other: &Self) -> Self {
+        Aabb3 {
+            min: Point3 { x: min(self.min.x, other.min.x), y: min(self.min.y, other.min.y), z: min(self.min.z, other.min.z) },
+            max: Point3 { x: max(self.max.x, other.max.x), y: max(self.max.y, other.max.y), z: max(self.max.z, other.max.z) }
+        }
+    }
+}
+
+/// A direction on a grid where `y` grows downwards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dir {
+    Up, Right, Down, Left
+}
+
+impl Dir {
+    /// Clockwise, starting upwards.
+    pub const ALL: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+
+    /// A quarter turn clockwise.
+    pub fn rotate_right(self) -> Dir {
+        match self {
+            Dir::Up => Dir::Right,
+            Dir::Right => Dir::Down,
+            Dir::Down => Dir::Left,
+            Dir::Left => Dir::Up
+        }
+    }
+
+    /// A quarter turn counter-clockwise.
+    pub fn rotate_left(self) -> Dir {
+        self.opposite().rotate_right()
+    }
+
+    pub fn opposite(self) -> Dir {
+        self.rotate_right().rotate_right()
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Dir::Up | Dir::Down)
+    }
+
+    /// One step in this direction.
+    pub fn offset(self) -> Point2<i64> {
+        match self {
+            Dir::Up => Point2 { x: 0, y: -1 },
+            Dir::Right => Point2 { x: 1, y: 0 },
+            Dir::Down => Point2 { x: 0, y: 1 },
+            Dir::Left => Point2 { x: -1, y: 0 }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances_in_the_plane() {
+        let (a, b) = (Point2::new(1i64, 5), Point2::new(4, 1));
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+        assert_eq!(a.euclidean_sq(&b), 25);
+        assert_eq!(b.euclidean_sq(&a), 25);
+        let (a, b) = (Point2::new(2u64, 9), Point2::new(7, 3));
+        assert_eq!((a.manhattan(&b), a.euclidean_sq(&b)), (11, 61));
+    }
+
+    #[test]
+    fn distances_in_space() {
+        let (a, b) = (Point3::new(-1i64, 2, 3), Point3::new(2, -2, 15));
+        assert_eq!(a.manhattan(&b), 19);
+        assert_eq!(a.chebyshev(&b), 12);
+        assert_eq!(a.euclidean_sq(&b), 169);
+        assert_eq!(a.euclidean_sq(&a), 0);
+    }
+
+    #[test]
+    fn points_parse_and_combine() {
+        assert_eq!("3, -4".parse::<Point2<i64>>().unwrap(), Point2::new(3, -4));
+        assert_eq!("1,2,3".parse::<Point3<u32>>().unwrap(), Point3::new(1, 2, 3));
+        assert!("1,2".parse::<Point3<u32>>().is_err());
+        assert_eq!(Point2::new(1, 2) + Point2::new(3, 4) * 2, Point2::new(7, 10));
+        assert_eq!(-(Point3::new(1, 2, 3) - Point3::new(2, 2, 2)), Point3::new(1, 0, -1));
+    }
+
+    #[test]
+    fn dir_turns() {
+        for dir in Dir::ALL {
+            assert_eq!(dir.rotate_right().rotate_left(), dir);
+            assert_eq!(dir.opposite().opposite(), dir);
+            assert_eq!(dir.offset() + dir.opposite().offset(), Point2::new(0, 0));
+            assert_ne!(dir.is_vertical(), dir.rotate_right().is_vertical());
+        }
+        assert_eq!(Dir::Up.rotate_right(), Dir::Right);
+        assert_eq!(Dir::Up.rotate_left(), Dir::Left);
+        assert_eq!(Dir::Left.opposite(), Dir::Right);
+        assert_eq!(Dir::Down.offset(), Point2::new(0, 1));
+    }
+
+    #[test]
+    fn aabb_bounds_are_inclusive() {
+        let unit = Aabb::from_corners(&Point2::new(1u64, 1), &Point2::new(0, 0));
+        assert_eq!(unit.min, Point2::new(0, 0));
+        assert_eq!(unit.area(), 4);
+        assert_eq!(Aabb::from_corners(&Point2::new(3u64, 5), &Point2::new(3, 5)).area(), 1);
+        // Day 9's sample: the largest rectangle between red tiles 2,5 and 11,1.
+        assert_eq!(Aabb::from_corners(&Point2::new(2u64, 5), &Point2::new(11, 1)).area(), 50);
+        assert!(unit.contains(&Point2::new(1, 1)));
+        assert!(unit.contains(&Point2::new(0, 1)));
+        assert!(!unit.contains(&Point2::new(2, 1)));
+    }
+
+    #[test]
+    fn aabb_union_and_intersection() {
+        let a = Aabb::from_corners(&Point2::new(0i64, 0), &Point2::new(2, 2));
+        let b = Aabb::from_corners(&Point2::new(2i64, -1), &Point2::new(4, 1));
+        assert_eq!(a.union(&b), Aabb::from_corners(&Point2::new(0, -1), &Point2::new(4, 2)));
+        assert_eq!(a.intersection(&b), Some(Aabb::from_corners(&Point2::new(2, 0), &Point2::new(2, 1))));
+        assert_eq!(a.intersection(&b).map(|i| i.area()), Some(2));
+        let c = Aabb::from_corners(&Point2::new(3i64, 3), &Point2::new(5, 5));
+        assert_eq!(a.intersection(&c), None);
+        assert_eq!(a.union(&a), a);
+    }
+
+    #[test]
+    fn aabb3_matches_aabb() {
+        let a = Aabb3::from_corners(&Point3::new(1i64, 1, 1), &Point3::new(0, 0, 0));
+        assert_eq!(a.volume(), 8);
+        assert!(a.contains(&Point3::new(1, 0, 1)));
+        assert!(!a.contains(&Point3::new(1, 0, 2)));
+        let b = Aabb3::from_corners(&Point3::new(1i64, 1, 1), &Point3::new(3, 3, 3));
+        assert_eq!(a.intersection(&b), Some(Aabb3::from_corners(&Point3::new(1, 1, 1), &Point3::new(1, 1, 1))));
+        assert_eq!(a.union(&b).volume(), 64);
+        assert_eq!(a.intersection(&Aabb3::from_corners(&Point3::new(0, 0, 2), &Point3::new(1, 1, 2))), None);
+    }
+}
@@ -0,0 +1,122 @@
+﻿use std::env;
+use std::io::Read;
+use crate::error::Error;
+
+pub static DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub static USER_AGENT: &str = concat!("github.com/Gollorum/advent-of-code-2025 v", env!("CARGO_PKG_VERSION"));
+
+pub struct Response {
+    pub status: u16,
+    pub body: String
+}
+
+/// The HTTP operations the site clients need, so they can run against a local stub instead of the real server.
+pub trait HttpClient {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<Response, Error>;
+    fn post_form(&self, url: &str, headers: &[(&str, &str)], form: &[(&str, &str)]) -> Result<Response, Error>;
+}
+
+/// The headers every request to the site carries: who is asking, and for which account.
+pub fn session_headers(cookie: &str) -> [(&'static str, &str); 2] {
+    [("User-Agent", USER_AGENT), ("Cookie", cookie)]
+}
+
+/// Where requests go: `AOC_BASE_URL` if set, the real site otherwise.
+pub fn base_url() -> String {
+    env::var("AOC_BASE_URL")
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|_| DEFAULT_BASE_URL.to_string())
+}
+
+pub struct UreqClient {
+    agent: ureq::Agent
+}
+
+impl UreqClient {
+    pub fn new() -> UreqClient {
+        UreqClient { agent: ureq::AgentBuilder::new().build() }
+    }
+}
+
+impl Default for UreqClient {
+    fn default() -> Self {
+        UreqClient::new()
+    }
+}
+
+fn into_response(result: Result<ureq::Response, ureq::Error>) -> Result<Response, Error> {
+    let response = match result {
+        Ok(response) => response,
+        Err(ureq::Error::Status(_, response)) => response,
+        Err(err) => return Err(Error::Network { message: "Request failed".to_string(), source: Some(Box::new(err)) })
+    };
+    let status = response.status();
+    let mut body = String::new();
+    response.into_reader().read_to_string(&mut body)?;
+    Ok(Response { status, body })
+}
+
+impl HttpClient for UreqClient {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<Response, Error> {
+        let mut request = self.agent.get(url);
+        for (name, value) in headers {
+            request = request.set(name, value);
+        }
+        into_response(request.call())
+    }
+
+    fn post_form(&self, url: &str, headers: &[(&str, &str)], form: &[(&str, &str)]) -> Result<Response, Error> {
+        let mut request = self.agent.post(url);
+        for (name, value) in headers {
+            request = request.set(name, value);
+        }
+        into_response(request.send_form(form))
+    }
+}
+
+/// A client that answers every request with a fixed response and remembers what was asked.
+#[cfg(test)]
+pub(crate) mod fake {
+    use std::cell::RefCell;
+    use super::*;
+
+    pub struct Request {
+        pub url: String,
+        pub headers: Vec<(String, String)>,
+        pub form: Vec<(String, String)>
+    }
+
+    impl Request {
+        pub fn header(&self, name: &str) -> Option<&str> {
+            self.headers.iter().find(|(n, _)| n == name).map(|(_, value)| value.as_str())
+        }
+    }
+
+    pub struct FakeClient {
+        status: u16,
+        body: String,
+        pub requests: RefCell<Vec<Request>>
+    }
+
+    impl FakeClient {
+        pub fn new(status: u16, body: &str) -> FakeClient {
+            FakeClient { status, body: body.to_string(), requests: RefCell::new(vec![]) }
+        }
+
+        fn respond(&self, url: &str, headers: &[(&str, &str)], form: &[(&str, &str)]) -> Result<Response, Error> {
+            let owned = |pairs: &[(&str, &str)]| pairs.iter().map(|(n, v)| (n.to_string(), v.to_string())).collect();
+            self.requests.borrow_mut().push(Request { url: url.to_string(), headers: owned(headers), form: owned(form) });
+            Ok(Response { status: self.status, body: self.body.clone() })
+        }
+    }
+
+    impl HttpClient for FakeClient {
+        fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<Response, Error> {
+            self.respond(url, headers, &[])
+        }
+
+        fn post_form(&self, url: &str, headers: &[(&str, &str)], form: &[(&str, &str)]) -> Result<Response, Error> {
+            self.respond(url, headers, form)
+        }
+    }
+}
@@ -0,0 +1,112 @@
+﻿use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::Read;
+use crate::samples;
+use crate::solution::Params;
+use crate::utils;
+use crate::error::Error;
+
+/// Where a puzzle input comes from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputSource {
+    /// The personal puzzle input, `inputs/dayNN.txt`.
+    Actual,
+    /// An example from the puzzle text: the `[[sample]]` of samples.toml with that name,
+    /// or the day's first one without a name.
+    Sample(Option<String>),
+    Path(String),
+    Stdin,
+    Inline(String)
+}
+
+/// The input files compiled into the binary by build.rs.
+#[cfg(feature = "embed-inputs")]
+pub mod embedded {
+    include!(concat!(env!("OUT_DIR"), "/embedded_inputs.rs"));
+
+    /// The contents `path` had at build time, if the file existed then.
+    pub fn get(path: &str) -> Option<&'static str> {
+        INPUTS.iter().find(|(p, _)| *p == path).map(|(_, input)| *input)
+    }
+}
+
+#[cfg(not(feature = "embed-inputs"))]
+pub mod embedded {
+    pub fn get(_path: &str) -> Option<&'static str> {
+        None
+    }
+}
+
+/// Prefers the copy in the binary and reads the file only if there is none.
+pub(crate) fn read_input_file(path: &str) -> Result<String, Error> {
+    match embedded::get(path) {
+        Some(input) => Ok(input.to_string()),
+        None => Ok(utils::read_file(path)?)
+    }
+}
+
+pub fn actual_path(day: u8) -> String {
+    format!("inputs/day{:02}.txt", day)
+}
+
+pub fn sample_path(day: u8, name: Option<&str>) -> String {
+    match name {
+        Some(name) => format!("inputs/day{:02}_sample_{}.txt", day, name),
+        None => format!("inputs/day{:02}_sample.txt", day)
+    }
+}
+
+impl InputSource {
+    pub fn is_sample(&self) -> bool {
+        matches!(self, InputSource::Sample(_))
+    }
+
+    /// The file this source reads, or a stand-in like `<stdin>` for the others.
+    pub fn path(&self, day: u8) -> String {
+        match self {
+            InputSource::Actual => actual_path(day),
+            InputSource::Sample(name) => samples::resolve(day, name.as_deref())
+                .map_or_else(|_| format!("<sample '{}'>", name.as_deref().unwrap_or("sample")), |sample| sample.path()),
+            InputSource::Path(path) => path.clone(),
+            InputSource::Stdin => "<stdin>".to_string(),
+            InputSource::Inline(_) => "<inline>".to_string()
+        }
+    }
+
+    /// The parameters the source asks for on top of the solution's defaults, like the `params` of a sample.
+    pub fn params(&self, day: u8) -> Params {
+        match self {
+            InputSource::Sample(name) => samples::resolve(day, name.as_deref()).map(|sample| sample.params).unwrap_or_default(),
+            _ => Params::default()
+        }
+    }
+
+    /// The input text, without a byte order mark and with `\n` line endings.
+    pub fn read(&self, day: u8) -> Result<String, Error> {
+        let input = match self {
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                input
+            },
+            InputSource::Inline(input) => input.clone(),
+            InputSource::Actual => read_input_file(&actual_path(day))?,
+            InputSource::Sample(name) => samples::resolve(day, name.as_deref())?.read()?,
+            InputSource::Path(path) => utils::read_file(path)?
+        };
+        Ok(utils::normalize(&input).into_owned())
+    }
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::Actual => write!(f, "actual"),
+            InputSource::Sample(None) => write!(f, "sample"),
+            InputSource::Sample(Some(name)) => write!(f, "sample '{}'", name),
+            InputSource::Path(path) => write!(f, "{}", path),
+            InputSource::Stdin => write!(f, "<stdin>"),
+            InputSource::Inline(_) => write!(f, "<inline>")
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,28 @@
+pub mod answers;
+pub mod bench;
+pub mod error;
+pub mod fetch;
+pub mod geom;
+pub mod http;
+pub mod input;
+pub mod lint;
+pub mod memory;
+pub mod output;
+pub mod samples;
+pub mod scaffold;
+pub mod search;
+pub mod solution;
+pub mod submit;
+pub mod suite;
+pub mod utils;
+pub mod verify;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
\ No newline at end of file
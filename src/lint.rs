@@ -0,0 +1,220 @@
+use std::any;
+use std::ops::Range;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::str::FromStr;
+use lazy_static::lazy_static;
+use regex::Regex;
+use crate::input::InputSource;
+use crate::solution::Runner;
+use crate::utils::Line;
+use crate::error::Error;
+
+lazy_static! {
+    static ref NUMBER_REGEX: Regex = Regex::new(r"-?\d+").unwrap();
+}
+
+/// Every line of `input`, blank ones included, numbered from 1.
+fn all_lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input.split('\n').enumerate().map(|(i, text)| Line { number: i + 1, text })
+}
+
+fn problem(line: &Line, message: String, columns: Range<usize>) -> Error {
+    Error::Parse(message).at_line(line.number, line.text, columns)
+}
+
+/// Checks every input needs: no trailing whitespace and no tabs.
+pub fn whitespace(input: &str) -> Vec<Error> {
+    let mut problems = trailing_whitespace(input);
+    problems.extend(tabs(input));
+    problems
+}
+
+pub fn trailing_whitespace(input: &str) -> Vec<Error> {
+    all_lines(input)
+        .filter(|line| line.trim_end().len() < line.len())
+        .map(|line| problem(&line, "Trailing whitespace".to_string(), line.trim_end().len()..line.len()))
+        .collect()
+}
+
+pub fn tabs(input: &str) -> Vec<Error> {
+    all_lines(input)
+        .flat_map(|line| line.match_indices('\t')
+            .map(|(i, _)| problem(&line, "Tab character".to_string(), i..i + 1))
+            .collect::<Vec<Error>>())
+        .collect()
+}
+
+/// Rows of a grid that are shorter or longer than its first row.
+pub fn ragged_rows(input: &str) -> Vec<Error> {
+    let rows = all_lines(input).filter(|line| !line.trim().is_empty()).collect::<Vec<Line>>();
+    let Some(first) = rows.first() else {
+        return vec![];
+    };
+    let width = first.trim_end().chars().count();
+    rows.iter()
+        .filter_map(|row| {
+            let text = row.trim_end();
+            let columns = text.chars().count();
+            if columns == width {
+                return None;
+            }
+            // Underlines the surplus of a long row, or the end of a short one.
+            let start = text.char_indices().nth(width).map_or(text.len(), |(i, _)| i);
+            let message = format!("Row has {} columns, but line {} has {}", columns, first.number, width);
+            Some(problem(row, message, start..text.len()))
+        })
+        .collect()
+}
+
+/// Reports a missing blank line before the first line that `starts_section` accepts.
+pub fn missing_separator(input: &str, starts_section: impl Fn(&str) -> bool) -> Vec<Error> {
+    let lines = all_lines(input).collect::<Vec<Line>>();
+    let first = lines.iter().position(|line| !line.trim().is_empty() && starts_section(line));
+    match first {
+        Some(i) if i > 0 && !lines[i - 1].trim().is_empty() =>
+            vec![lines[i].error(Error::Parse("Missing blank line before this line".to_string()))],
+        _ => vec![]
+    }
+}
+
+/// Numbers in `input` that `T`, the type the solution parses them into, cannot hold.
+pub fn numbers<T: FromStr>(input: &str) -> Vec<Error> {
+    all_lines(input)
+        .flat_map(|line| NUMBER_REGEX.find_iter(line.text)
+            .filter(|m| m.as_str().parse::<T>().is_err())
+            .map(|m| problem(&line, format!("{} does not fit in {}", m.as_str(), any::type_name::<T>()), m.range()))
+            .collect::<Vec<Error>>())
+        .collect()
+}
+
+/// Runs the parser of `solution` on `input` and returns the error it stops at, if any.
+/// A panic is reported as an error too, since lint is meant to find the inputs that cause them,
+/// and the panic hook is silenced meanwhile so it is not printed twice.
+pub fn parse_error(solution: &dyn Runner, input: &str) -> Option<Error> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| solution.check_parse(input)));
+    panic::set_hook(hook);
+    match result {
+        Ok(result) => result.err(),
+        Err(payload) => {
+            let message = payload.downcast_ref::<&str>().map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown cause".to_string());
+            Some(Error::Failed(format!("The parser panicked: {}", message)))
+        }
+    }
+}
+
+/// The problems of `input`, including the error the parser of `solution` stops at, ordered by position.
+/// Of several problems at the same place, e.g. a number that is too large and the parse error it causes,
+/// only the first check's is kept.
+fn problems(solution: &dyn Runner, input: &str) -> Vec<Error> {
+    let mut problems = solution.lint(input);
+    problems.extend(parse_error(solution, input));
+    let position = |p: &Error| p.location().map(|l| (l.line, l.columns.start));
+    problems.sort_by_key(position);
+    problems.dedup_by(|later, earlier| position(later).is_some() && position(later) == position(earlier));
+    problems
+}
+
+/// Reads the input of `solution` from `source` and returns its problems, ordered by position.
+pub fn lint(solution: &dyn Runner, source: &InputSource) -> Result<Vec<Error>, Error> {
+    let day = solution.day();
+    let input = source.read(day)?;
+    let path = source.path(day);
+    Ok(problems(solution, &input).into_iter().map(|p| p.in_file(&path)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::solution::{Params, Solution};
+    use crate::utils;
+    use super::*;
+
+    /// Held by tests that run parsers, since [`parse_error`] swaps the process-wide panic hook.
+    static HOOK: Mutex<()> = Mutex::new(());
+
+    struct Panicking;
+
+    impl Solution for Panicking {
+        type Input = ();
+
+        fn day(&self) -> u8 { 0 }
+
+        fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+            match input {
+                "panic" => panic!("index out of bounds"),
+                "error" => Err(Error::Parse("Bad line".to_string()).at_line(1, input, 0..input.len())),
+                _ => Ok(())
+            }
+        }
+
+        fn part_1(&self, _: &Self::Input, _: &Params) -> Result<String, Error> { unreachable!() }
+        fn part_2(&self, _: &Self::Input, _: &Params) -> Result<String, Error> { unreachable!() }
+    }
+
+    /// Parses one small number per line, like a real solution that checks its numbers.
+    struct Bytes;
+
+    impl Solution for Bytes {
+        type Input = Vec<u8>;
+
+        fn day(&self) -> u8 { 0 }
+
+        fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+            utils::lines(input)?.iter().map(|line| line.parse::<u8>()).collect()
+        }
+
+        fn lint(&self, input: &str) -> Vec<Error> {
+            let mut problems = whitespace(input);
+            problems.extend(numbers::<u8>(input));
+            problems
+        }
+
+        fn part_1(&self, _: &Self::Input, _: &Params) -> Result<String, Error> { unreachable!() }
+        fn part_2(&self, _: &Self::Input, _: &Params) -> Result<String, Error> { unreachable!() }
+    }
+
+    #[test]
+    fn whitespace_problems_are_at_their_own_columns() {
+        let problems = whitespace("1,2,3} \t");
+        let columns = problems.iter().map(|p| p.location().unwrap().column()).collect::<Vec<usize>>();
+        assert_eq!(columns, [7, 8]);
+    }
+
+    #[test]
+    fn parse_errors_and_panics_are_problems() {
+        let _hook = HOOK.lock().unwrap();
+        assert!(parse_error(&Panicking, "fine").is_none());
+        let error = parse_error(&Panicking, "error").unwrap();
+        assert_eq!(error.location().and_then(|l| l.line), Some(1));
+        let panic = parse_error(&Panicking, "panic").unwrap();
+        assert_eq!(panic.to_string(), "The parser panicked: index out of bounds");
+    }
+
+    #[test]
+    fn parser_panics_are_not_printed() {
+        static PRINTED: AtomicUsize = AtomicUsize::new(0);
+        let _hook = HOOK.lock().unwrap();
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(|_| { PRINTED.fetch_add(1, Ordering::SeqCst); }));
+        assert!(parse_error(&Panicking, "panic").is_some());
+        assert_eq!(PRINTED.load(Ordering::SeqCst), 0);
+        // The hook that was installed before is back.
+        let _ = panic::catch_unwind(|| panic!("printed"));
+        assert_eq!(PRINTED.load(Ordering::SeqCst), 1);
+        panic::set_hook(default);
+    }
+
+    #[test]
+    fn a_problem_is_reported_once() {
+        let _hook = HOOK.lock().unwrap();
+        let problems = problems(&Bytes, "12\n300\n7 ");
+        let found = problems.iter().map(|p| p.to_string()).collect::<Vec<String>>();
+        assert_eq!(found, ["300 does not fit in u8 (line 2:1)", "Trailing whitespace (line 3:2)"]);
+    }
+}
@@ -1,27 +1,199 @@
-extern crate core;
-use arboard::Clipboard;
-use crate::utils::ErrorMsg;
-
-mod utils;
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
+use std::time::Duration;
+use aoc::{answers, bench, fetch, http, lint, output, samples, scaffold, solution, submit, suite, verify};
+use aoc::answers::Answers;
+use aoc::error::Error;
+use aoc::bench::{BenchOptions, BenchReport, Baselines};
+use aoc::fetch::Fetched;
+use aoc::http::UreqClient;
+use aoc::input::InputSource;
+use aoc::memory::PeakAlloc;
+use aoc::output::{Answer, OutputSpec};
+use aoc::samples::{Manifest, SampleReport};
+use aoc::solution::{Params, Part};
+use aoc::submit::{History, Verdict};
+use aoc::suite::{Record, Report, Status};
+use aoc::verify::{Check, CheckReport};
+use crate::cli::{Command, Format, SuiteArgs};
+
+mod cli;
+
+#[global_allocator]
+static ALLOCATOR: PeakAlloc = PeakAlloc;
 
 fn main() {
-    let response = ErrorMsg::result_to_string(day10::run_part_2(true));
-    println!("{}", &response);
-    copy_to_clipboard(&response).unwrap_or(());
+    match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Command::Run { day, part, input, params, outputs, format }) => {
+            let record = run(day, part, &input, &params);
+            if format == Format::Json {
+                suite::print_json(&Report::from(&record));
+            }
+            let answer = Answer::new(day, part, input.to_string(), record.result());
+            for spec in outputs {
+                if format == Format::Json && matches!(spec, OutputSpec::Stdout) {
+                    continue;
+                }
+                if let Err(err) = spec.open().and_then(|mut sink| sink.emit(&answer)) {
+                    eprintln!("Warning: {}", err);
+                }
+            }
+            if let Some(err) = record.error() {
+                if err.location().is_some() {
+                    eprintln!("{}", err.report());
+                }
+                std::process::exit(err.exit_code());
+            }
+        },
+        Ok(Command::All { suite }) => run_all(&suite).unwrap_or_else(exit_with),
+        Ok(Command::Verify { suite, record }) => run_verify(&suite, record).unwrap_or_else(exit_with),
+        Ok(Command::Samples { day, timeout, format }) => run_samples(day, timeout, format).unwrap_or_else(exit_with),
+        Ok(Command::Bench { day, part, suite, options, save }) =>
+            run_bench(day, part, &suite, &options, save).unwrap_or_else(exit_with),
+        Ok(Command::New { day, expect }) => {
+            let touched = scaffold::new_day(day, expect.as_deref()).unwrap_or_else(exit_with);
+            touched.iter().for_each(|path| println!("Wrote {}", path));
+            println!("Paste the sample into {}", samples::SAMPLES);
+            if expect.is_none() {
+                println!("and replace {} there with its answer for part 1", scaffold::PLACEHOLDER_ANSWER);
+            }
+        },
+        Ok(Command::Lint { day, input }) => run_lint(day, &input).unwrap_or_else(exit_with),
+        Ok(Command::Fetch { day }) => {
+            let fetched = fetch::session_token()
+                .and_then(|token| fetch::fetch_input(&UreqClient::new(), &http::base_url(), &token, day));
+            match fetched.unwrap_or_else(exit_with) {
+                Fetched::Cached(path) => println!("{} is already cached", path),
+                Fetched::Downloaded(path) => println!("Saved input to {}", path)
+            }
+        },
+        Ok(Command::Submit { day, part, answer }) => println!("{}", run_submit(day, part, answer).unwrap_or_else(exit_with)),
+        Ok(Command::Help) => println!("{}", cli::USAGE),
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, cli::USAGE);
+            std::process::exit(err.exit_code());
+        }
+    }
+}
+
+/// Prints `err` and exits with the code of its category.
+fn exit_with<T>(err: Error) -> T {
+    eprintln!("{}", err.report());
+    std::process::exit(err.exit_code());
+}
+
+fn run(day: u8, part: Part, input: &InputSource, overrides: &Params) -> Record {
+    let solution = solution::find(day).unwrap_or_else(exit_with);
+    let record = suite::run_one(solution, part, input, overrides, None);
+    eprintln!("Parsed in {:?}, solved in {:?}", record.parse_time, record.solve_time);
+    record
+}
+
+fn run_all(suite: &SuiteArgs) -> Result<(), Error> {
+    let records = suite::run_all(&solution::registry(), &suite.kinds, suite.timeout, suite.jobs);
+    match suite.format {
+        Format::Text => suite::print_table(&records),
+        Format::Json => records.iter().for_each(|r| suite::print_json(&Report::from(r)))
+    }
+    let failures = records.iter().filter(|r| !matches!(r.status, Status::Ok)).count();
+    if failures > 0 {
+        return Err(Error::Failed(format!("{} part(s) did not run to an answer", failures)));
+    }
+    Ok(())
 }
 
-fn copy_to_clipboard(content: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let mut clipboard = Clipboard::new()?;
-    clipboard.set_text(content)?;
+fn run_verify(suite: &SuiteArgs, record: bool) -> Result<(), Error> {
+    let mut answers = Answers::load(answers::ANSWERS)?;
+    let samples = Manifest::load(samples::SAMPLES)?;
+    let checks = suite::run_all(&solution::registry(), &suite.kinds, suite.timeout, suite.jobs).into_iter()
+        .map(|r| verify::check(r, &answers, &samples))
+        .collect::<Vec<Check>>();
+    match suite.format {
+        Format::Text => verify::print_checks(&checks),
+        Format::Json => checks.iter().for_each(|c| suite::print_json(&CheckReport::from(c)))
+    }
+    if record {
+        let added = verify::record_unknown(&checks, &mut answers);
+        answers.save(answers::ANSWERS)?;
+        eprintln!("Recorded {} new answer(s) in {}", added, answers::ANSWERS);
+    }
+    let failures = checks.iter().filter(|c| c.verdict.is_failure()).count();
+    if failures > 0 {
+        return Err(Error::Failed(format!("{} check(s) failed", failures)));
+    }
     Ok(())
 }
+
+fn run_samples(day: Option<u8>, timeout: Option<Duration>, format: Format) -> Result<(), Error> {
+    let manifest = Manifest::load(samples::SAMPLES)?;
+    let checks = samples::run(&manifest, day, timeout)?;
+    if checks.is_empty() {
+        return Err(Error::Usage(format!("No samples with expected answers in {}", samples::SAMPLES)));
+    }
+    match format {
+        Format::Text => samples::print_sample_checks(&checks),
+        Format::Json => checks.iter().for_each(|c| suite::print_json(&SampleReport::from(c)))
+    }
+    let failures = checks.iter().filter(|c| c.check.verdict.is_failure()).count();
+    if failures > 0 {
+        return Err(Error::Failed(format!("{} sample(s) failed", failures)));
+    }
+    Ok(())
+}
+
+fn run_lint(day: u8, input: &InputSource) -> Result<(), Error> {
+    let problems = lint::lint(solution::find(day)?, input)?;
+    let path = input.path(day);
+    if problems.is_empty() {
+        println!("No problems found in {}", path);
+        return Ok(());
+    }
+    problems.iter().for_each(|p| println!("{}\n", p.report()));
+    Err(Error::Parse(format!("{} problem(s) in {}", problems.len(), path)))
+}
+
+fn run_bench(day: Option<u8>, part: Option<Part>, suite: &SuiteArgs, options: &BenchOptions, save: bool) -> Result<(), Error> {
+    let solutions = match day {
+        Some(day) => vec![solution::find(day)?],
+        None => solution::registry()
+    };
+    let parts = part.map_or(vec![Part::One, Part::Two], |p| vec![p]);
+    let mut baselines = Baselines::load(bench::BASELINES)?;
+    let results = bench::run(&solutions, &parts, &suite.kinds, &baselines, options);
+    match suite.format {
+        Format::Text => bench::print_results(&results),
+        Format::Json => results.iter().for_each(|r| suite::print_json(&BenchReport::from(r)))
+    }
+    if save {
+        let stored = bench::update_baselines(&results, &mut baselines);
+        baselines.save(bench::BASELINES)?;
+        eprintln!("Stored {} baseline(s) in {}", stored, bench::BASELINES);
+    }
+    let errors = results.iter().filter(|r| r.outcome.is_err()).count();
+    if errors > 0 {
+        return Err(Error::Failed(format!("{} benchmark(s) could not be measured", errors)));
+    }
+    let regressions = results.iter().filter(|r| r.is_regression()).count();
+    if regressions > 0 {
+        return Err(Error::Failed(format!("{} benchmark(s) regressed by more than {}%", regressions, options.threshold)));
+    }
+    Ok(())
+}
+
+fn run_submit(day: u8, part: Part, answer: Option<String>) -> Result<Verdict, Error> {
+    let answer = match answer {
+        Some(answer) => answer,
+        None => output::clipboard::paste()
+            .map_err(|e| Error::Usage(format!("No answer given and {}", e)))?
+    };
+    let answer = answer.trim();
+    let token = fetch::session_token()?;
+    let mut history = History::load(submit::HISTORY)?;
+    let verdict = submit::submit(&UreqClient::new(), &http::base_url(), &token, &mut history, day, part, answer);
+    history.save(submit::HISTORY)?;
+    let verdict = verdict?;
+    if verdict == Verdict::Correct {
+        let mut answers = Answers::load(answers::ANSWERS)?;
+        answers.record(day, part, answer);
+        answers.save(answers::ANSWERS)?;
+    }
+    Ok(verdict)
+}
\ No newline at end of file
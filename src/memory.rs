@@ -0,0 +1,77 @@
+﻿use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+/// Set by the first allocation through [`PeakAlloc`], which only happens if it is the global allocator.
+static TRACKING: AtomicBool = AtomicBool::new(false);
+
+/// The system allocator, plus bookkeeping of how many bytes are live and the most that were live at once.
+pub struct PeakAlloc;
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !TRACKING.load(Ordering::Relaxed) {
+            TRACKING.store(true, Ordering::Relaxed);
+        }
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            if new_size >= layout.size() {
+                let grown = new_size - layout.size();
+                let current = CURRENT.fetch_add(grown, Ordering::Relaxed) + grown;
+                PEAK.fetch_max(current, Ordering::Relaxed);
+            } else {
+                CURRENT.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+            }
+        }
+        new_ptr
+    }
+}
+
+/// Starts a new measurement and returns the live bytes it is relative to.
+/// There is only one peak for the whole process, so measurements must not overlap.
+pub fn reset_peak() -> usize {
+    let current = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(current, Ordering::Relaxed);
+    current
+}
+
+/// Whether [`PeakAlloc`] is the global allocator, so that measurements mean something.
+pub fn is_tracking() -> bool {
+    TRACKING.load(Ordering::Relaxed)
+}
+
+/// The most bytes that were live at once since `reset_peak` returned `baseline`, on top of that baseline.
+/// None unless [`PeakAlloc`] is the global allocator.
+pub fn peak_since(baseline: usize) -> Option<usize> {
+    is_tracking().then(|| PEAK.load(Ordering::Relaxed).saturating_sub(baseline))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nothing_is_measured_without_the_allocator() {
+        // The library's tests run on the system allocator.
+        let baseline = reset_peak();
+        let grown = vec![0u8; 1 << 16];
+        assert!(!is_tracking());
+        assert_eq!(peak_since(baseline), None);
+        drop(grown);
+    }
+}
@@ -0,0 +1,161 @@
+﻿use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::str::FromStr;
+use serde::Serialize;
+use crate::solution::Part;
+use crate::error::Error;
+
+/// A finished `aoc run`, as handed to every sink.
+#[derive(Serialize)]
+pub struct Answer {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>
+}
+
+impl Answer {
+    pub fn new(day: u8, part: Part, input: String, result: Result<String, String>) -> Answer {
+        let (answer, error) = match result {
+            Ok(answer) => (Some(answer), None),
+            Err(err) => (None, Some(err))
+        };
+        Answer { day, part: part.number(), input, answer, error }
+    }
+
+    /// The answer, or the error message if there is none.
+    pub fn text(&self) -> &str {
+        self.answer.as_deref().or(self.error.as_deref()).unwrap_or_default()
+    }
+}
+
+pub trait Sink {
+    fn emit(&mut self, answer: &Answer) -> Result<(), Error>;
+}
+
+#[derive(Clone, Debug)]
+pub enum OutputSpec {
+    Stdout,
+    Clipboard,
+    /// Overwrites the file with the answer text.
+    File(String),
+    /// Appends one JSON object per answer.
+    JsonLines(String)
+}
+
+impl OutputSpec {
+    /// Stdout, plus the clipboard when it is compiled in.
+    pub fn defaults() -> Vec<OutputSpec> {
+        if cfg!(feature = "clipboard") {
+            vec![OutputSpec::Stdout, OutputSpec::Clipboard]
+        } else {
+            vec![OutputSpec::Stdout]
+        }
+    }
+
+    pub fn open(&self) -> Result<Box<dyn Sink>, Error> {
+        match self {
+            OutputSpec::Stdout => Ok(Box::new(StdoutSink)),
+            OutputSpec::Clipboard => clipboard::open(),
+            OutputSpec::File(path) => Ok(Box::new(FileSink { path: path.clone() })),
+            OutputSpec::JsonLines(path) => Ok(Box::new(JsonLinesSink { path: path.clone() }))
+        }
+    }
+}
+
+impl FromStr for OutputSpec {
+    type Err = Error;
+    /// Parses `stdout`, `clipboard`, `file:PATH` or `jsonl:PATH`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "stdout" => Ok(OutputSpec::Stdout),
+            None if s == "clipboard" => Ok(OutputSpec::Clipboard),
+            Some(("file", path)) if !path.is_empty() => Ok(OutputSpec::File(path.to_string())),
+            Some(("jsonl", path)) if !path.is_empty() => Ok(OutputSpec::JsonLines(path.to_string())),
+            _ => Err(Error::Usage(format!("Invalid output: {} (expected stdout, clipboard, file:PATH or jsonl:PATH)", s)))
+        }
+    }
+}
+
+struct StdoutSink;
+
+impl Sink for StdoutSink {
+    fn emit(&mut self, answer: &Answer) -> Result<(), Error> {
+        println!("{}", answer.text());
+        Ok(())
+    }
+}
+
+struct FileSink {
+    path: String
+}
+
+impl Sink for FileSink {
+    fn emit(&mut self, answer: &Answer) -> Result<(), Error> {
+        fs::write(&self.path, answer.text())?;
+        Ok(())
+    }
+}
+
+struct JsonLinesSink {
+    path: String
+}
+
+impl Sink for JsonLinesSink {
+    fn emit(&mut self, answer: &Answer) -> Result<(), Error> {
+        let line = serde_json::to_string(answer)
+            .map_err(|e| Error::store("Failed to serialize answer", e))?;
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", line)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "clipboard")]
+pub mod clipboard {
+    use arboard::Clipboard;
+    use crate::error::Error;
+    use super::{Answer, Sink};
+
+    struct ClipboardSink;
+
+    impl Sink for ClipboardSink {
+        fn emit(&mut self, answer: &Answer) -> Result<(), Error> {
+            copy(answer.text())
+        }
+    }
+
+    pub fn open() -> Result<Box<dyn Sink>, Error> {
+        Ok(Box::new(ClipboardSink))
+    }
+
+    pub fn copy(content: &str) -> Result<(), Error> {
+        Clipboard::new()
+            .and_then(|mut clipboard| clipboard.set_text(content))
+            .map_err(|e| Error::Failed(format!("Failed to copy to clipboard: {}", e)))
+    }
+
+    pub fn paste() -> Result<String, Error> {
+        Clipboard::new()
+            .and_then(|mut clipboard| clipboard.get_text())
+            .map_err(|e| Error::Failed(format!("Failed to read clipboard: {}", e)))
+    }
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub mod clipboard {
+    use crate::error::Error;
+    use super::Sink;
+
+    pub fn open() -> Result<Box<dyn Sink>, Error> {
+        Err(Error::Usage("Built without clipboard support (enable the 'clipboard' feature)".to_string()))
+    }
+
+    pub fn paste() -> Result<String, Error> {
+        Err(Error::Usage("Built without clipboard support (enable the 'clipboard' feature)".to_string()))
+    }
+}
\ No newline at end of file
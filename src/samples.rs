@@ -0,0 +1,183 @@
+use std::path::Path;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::input;
+use crate::input::InputSource;
+use crate::solution;
+use crate::solution::{Params, Part};
+use crate::suite;
+use crate::utils;
+use crate::error::Error;
+use crate::verify;
+use crate::verify::{Check, CheckReport};
+
+pub static SAMPLES: &str = "samples.toml";
+
+/// One example from a puzzle text, with the answers it should produce.
+#[derive(Deserialize, Clone, Debug)]
+pub struct Sample {
+    pub day: u8,
+    /// Names the example. Unless `input` is given, it is read from `inputs/dayNN_sample_NAME.txt`,
+    /// or `inputs/dayNN_sample.txt` without a name.
+    pub name: Option<String>,
+    pub input: Option<String>,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+    /// Overrides the parameters the solution uses for samples.
+    #[serde(default)]
+    pub params: Params
+}
+
+impl Sample {
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or("sample")
+    }
+
+    pub fn expected(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref()
+        }
+    }
+
+    /// The source the solution's defaults for samples are chosen by, e.g. the smaller connection count of day 8.
+    fn params_source(&self) -> InputSource {
+        InputSource::Sample(self.name.clone())
+    }
+
+    pub fn source(&self) -> InputSource {
+        match &self.input {
+            Some(input) => InputSource::Inline(input.clone()),
+            None => InputSource::Path(self.path())
+        }
+    }
+
+    /// The file the input is in, or a stand-in naming the sample if it is inline.
+    pub fn path(&self) -> String {
+        match &self.input {
+            Some(_) => format!("<sample '{}'>", self.label()),
+            None => input::sample_path(self.day, self.name.as_deref())
+        }
+    }
+
+    /// The input, not yet normalized.
+    pub fn read(&self) -> Result<String, Error> {
+        match &self.input {
+            Some(input) => Ok(input.clone()),
+            None => input::read_input_file(&self.path())
+        }
+    }
+}
+
+/// The examples of all days, stored as a `[[sample]]` array.
+#[derive(Deserialize, Default, Debug)]
+pub struct Manifest {
+    #[serde(default, rename = "sample")]
+    pub samples: Vec<Sample>
+}
+
+impl Manifest {
+    /// Prefers the copy in the binary, like the input files.
+    pub fn load(path: &str) -> Result<Manifest, Error> {
+        let content = match input::embedded::get(path) {
+            Some(content) => content.to_string(),
+            None if !Path::new(path).exists() => return Ok(Manifest::default()),
+            None => utils::read_file(path)?
+        };
+        toml::from_str(&content)
+            .map_err(|e| Error::store(format!("Failed to parse '{}'", path), e))
+    }
+
+    /// The sample of `day` called `name`, or without a name the first sample of `day`.
+    pub fn find(&self, day: u8, name: Option<&str>) -> Option<&Sample> {
+        self.samples.iter()
+            .filter(|s| s.day == day)
+            .find(|s| name.is_none_or(|name| s.name.as_deref() == Some(name)))
+    }
+}
+
+/// Looks up the sample that [`InputSource::Sample`] stands for in [`SAMPLES`].
+pub fn resolve(day: u8, name: Option<&str>) -> Result<Sample, Error> {
+    let manifest = Manifest::load(SAMPLES)?;
+    manifest.find(day, name).cloned().ok_or_else(|| match name {
+        Some(name) => Error::Usage(format!("Day {} has no sample '{}' in {}", day, name, SAMPLES)),
+        None => Error::Usage(format!("Day {} has no sample in {}", day, SAMPLES))
+    })
+}
+
+pub struct SampleCheck {
+    pub name: String,
+    pub check: Check
+}
+
+/// Runs every part that `sample` has an expected answer for.
+pub fn run_sample(sample: &Sample, timeout: Option<Duration>) -> Result<Vec<SampleCheck>, Error> {
+    let solution = solution::find(sample.day)?;
+    let mut params = solution.default_params(&sample.params_source());
+    params.merge(&sample.params);
+    let source = sample.source();
+    Ok([Part::One, Part::Two].into_iter()
+        .filter_map(|part| sample.expected(part).map(|expected| (part, expected)))
+        .map(|(part, expected)| SampleCheck {
+            name: sample.label().to_string(),
+            check: verify::judge(suite::run_one(solution, part, &source, &params, timeout), Some(expected))
+        })
+        .collect())
+}
+
+/// Runs the samples of `day`, or of every day, in manifest order.
+pub fn run(manifest: &Manifest, day: Option<u8>, timeout: Option<Duration>) -> Result<Vec<SampleCheck>, Error> {
+    let mut checks = vec![];
+    for sample in manifest.samples.iter().filter(|s| day.is_none_or(|d| s.day == d)) {
+        checks.extend(run_sample(sample, timeout)?);
+    }
+    Ok(checks)
+}
+
+/// The machine-readable form of a [`SampleCheck`], printed by `--format json`.
+#[derive(Serialize)]
+pub struct SampleReport {
+    pub name: String,
+    #[serde(flatten)]
+    pub check: CheckReport
+}
+
+impl From<&SampleCheck> for SampleReport {
+    fn from(c: &SampleCheck) -> Self {
+        SampleReport { name: c.name.clone(), check: CheckReport::from(&c.check) }
+    }
+}
+
+pub fn print_sample_checks(checks: &[SampleCheck]) {
+    let labelled = checks.iter().map(|c| (c.name.clone(), &c.check)).collect::<Vec<(String, &Check)>>();
+    verify::print_labelled_checks(&labelled);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::verify::Verdict;
+    use super::*;
+
+    #[test]
+    fn sample_sources_resolve_through_the_manifest() {
+        let manifest = Manifest::load(SAMPLES).unwrap();
+        let first = manifest.find(8, None).unwrap();
+        assert_eq!(InputSource::Sample(None).read(8).unwrap(), first.input.clone().unwrap());
+        assert_eq!(InputSource::Sample(None).path(8), "<sample 'puzzle'>");
+        let named = InputSource::Sample(Some("five-connections".to_string()));
+        assert_eq!(named.params(8).get::<usize>("count").unwrap(), 5);
+        assert!(matches!(InputSource::Sample(Some("missing".to_string())).read(8), Err(Error::Usage(_))));
+        assert!(matches!(resolve(99, None), Err(Error::Usage(_))));
+    }
+
+    #[test]
+    fn every_sample_is_correct() {
+        let manifest = Manifest::load(SAMPLES).unwrap();
+        let checks = run(&manifest, None, None).unwrap();
+        assert!(!checks.is_empty(), "{} has no samples", SAMPLES);
+        for c in &checks {
+            assert!(matches!(c.check.verdict, Verdict::Correct), "day {} part {} of {}: {} ({:?})",
+                c.check.record.day, c.check.record.part, c.name, c.check.verdict, c.check.record.result());
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::fs;
+use std::path::Path;
+use lazy_static::lazy_static;
+use regex::Regex;
+use crate::input;
+use crate::samples;
+use crate::samples::Manifest;
+use crate::utils;
+use crate::error::Error;
+
+static TEMPLATE: &str = include_str!("../templates/day.rs.tmpl");
+static LIB: &str = "src/lib.rs";
+static REGISTRY: &str = "src/solution.rs";
+/// Stands in for the sample answer until the real one is known, so the sample keeps failing until then.
+pub static PLACEHOLDER_ANSWER: &str = "TODO";
+
+lazy_static! {
+    static ref MOD_REGEX: Regex = Regex::new(r"^(\s*)pub mod day(\d{2});$").unwrap();
+    static ref REGISTRY_REGEX: Regex = Regex::new(r"^(\s*)&crate::day(\d{2})::Day\d{2},$").unwrap();
+}
+
+fn module_path(day: u8) -> String {
+    format!("src/day{:02}.rs", day)
+}
+
+/// Inserts `line` among the lines matching `pattern`, whose second group is a day number, keeping them sorted by day.
+fn insert_sorted(content: &str, pattern: &Regex, day: u8, line: &str, path: &str) -> Result<String, Error> {
+    let mut lines = content.split('\n').collect::<Vec<&str>>();
+    let days = lines.iter().enumerate()
+        .filter_map(|(i, l)| pattern.captures(l).map(|c| (i, c[1].to_string(), c[2].parse::<u8>().unwrap_or(0))))
+        .collect::<Vec<(usize, String, u8)>>();
+    if days.iter().any(|(_, _, d)| *d == day) {
+        return Err(Error::Usage(format!("Day {} is already registered in {}", day, path)));
+    }
+    let (index, indent) = match days.iter().rev().find(|(_, _, d)| *d < day) {
+        Some((i, indent, _)) => (i + 1, indent.clone()),
+        None => days.first()
+            .map(|(i, indent, _)| (*i, indent.clone()))
+            .ok_or_else(|| Error::Failed(format!("Found no list of days to extend in {}", path)))?
+    };
+    let line = format!("{}{}", indent, line);
+    lines.insert(index, &line);
+    Ok(lines.join("\n"))
+}
+
+/// A `[[sample]]` for samples.toml with an empty input to paste the example into.
+fn sample_stub(day: u8, answer: &str) -> String {
+    format!("[[sample]]\nday = {}\nname = \"puzzle\"\npart1 = {:?}\ninput = \"\"\"\n\"\"\"\n", day, answer)
+}
+
+/// Creates `path` empty unless it exists. Returns whether it was created.
+fn touch(path: &str) -> Result<bool, Error> {
+    if Path::new(path).exists() {
+        return Ok(false);
+    }
+    if let Some(dir) = Path::new(path).parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, "")?;
+    Ok(true)
+}
+
+/// Generates `src/dayNN.rs` from the template and registers it, creates an empty actual input and adds a sample
+/// to samples.toml that expects `sample_answer` (or [`PLACEHOLDER_ANSWER`]) for part 1. The module's test fails
+/// until part 1 gives that answer. Returns the files it touched.
+/// Must run from the repository root. Nothing is written unless the day can be added completely.
+pub fn new_day(day: u8, sample_answer: Option<&str>) -> Result<Vec<String>, Error> {
+    if !(1..=25).contains(&day) {
+        return Err(Error::Usage(format!("Invalid day: {} (expected 1 to 25)", day)));
+    }
+    if !Path::new(LIB).exists() || !Path::new(REGISTRY).exists() {
+        return Err(Error::Usage(format!("{} not found: run aoc new from the repository root", LIB)));
+    }
+    let module = module_path(day);
+    if Path::new(&module).exists() {
+        return Err(Error::Usage(format!("{} already exists", module)));
+    }
+    let lib = insert_sorted(&utils::read_file(LIB)?, &MOD_REGEX, day,
+        &format!("pub mod day{:02};", day), LIB)?;
+    let registry = insert_sorted(&utils::read_file(REGISTRY)?, &REGISTRY_REGEX, day,
+        &format!("&crate::day{:02}::Day{:02},", day, day), REGISTRY)?;
+    let manifest = Manifest::load(samples::SAMPLES)?;
+
+    let source = TEMPLATE
+        .replace("{{NN}}", &format!("{:02}", day))
+        .replace("{{DAY}}", &day.to_string());
+    fs::write(&module, source)?;
+    fs::write(LIB, lib)?;
+    fs::write(REGISTRY, registry)?;
+    let mut touched = vec![module, LIB.to_string(), REGISTRY.to_string()];
+    let path = input::actual_path(day);
+    if touch(&path)? {
+        touched.push(path);
+    }
+    if manifest.find(day, None).is_none() {
+        let mut content = if Path::new(samples::SAMPLES).exists() { utils::read_file(samples::SAMPLES)? } else { String::new() };
+        if !content.is_empty() {
+            content = format!("{}\n\n", content.trim_end());
+        }
+        content.push_str(&sample_stub(day, sample_answer.unwrap_or(PLACEHOLDER_ANSWER)));
+        fs::write(samples::SAMPLES, content)?;
+        touched.push(samples::SAMPLES.to_string());
+    }
+    Ok(touched)
+}
@@ -0,0 +1,217 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use crate::utils;
+use crate::utils::num::Coord;
+use crate::error::Error;
+
+/// How a search keeps from expanding the same state more than once.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Visited {
+    /// Remembers every state it reached, so each is expanded at most once.
+    #[default]
+    Remember,
+    /// Remembers nothing. Only for graphs where states cannot repeat, like trees, since cycles never end.
+    Forget
+}
+
+/// A cheapest way from the start to a goal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path<S, C> {
+    /// Every state on the way, from the start to the goal.
+    pub states: Vec<S>,
+    pub cost: C
+}
+
+impl<S, C> Path<S, C> {
+    pub fn goal(&self) -> &S {
+        self.states.last().expect("a path contains at least its start")
+    }
+}
+
+/// The states a search has reached, each linked to the one it was reached from.
+struct Arena<S, C> {
+    nodes: Vec<(S, Option<usize>, C)>
+}
+
+impl<S: Clone, C: Copy> Arena<S, C> {
+    fn push(&mut self, state: S, parent: Option<usize>, cost: C) -> usize {
+        self.nodes.push((state, parent, cost));
+        self.nodes.len() - 1
+    }
+
+    fn path_to(&self, mut node: usize) -> Path<S, C> {
+        let cost = self.nodes[node].2;
+        let mut states = vec![self.nodes[node].0.clone()];
+        while let Some(parent) = self.nodes[node].1 {
+            states.push(self.nodes[parent].0.clone());
+            node = parent;
+        }
+        states.reverse();
+        Path { states, cost }
+    }
+}
+
+/// Finds a path from `start` to a state that `is_goal` accepts with as few steps as possible.
+/// Its cost is the number of steps. Fails only if the run is cancelled.
+pub fn bfs<S, I>(start: S, mut successors: impl FnMut(&S) -> I, mut is_goal: impl FnMut(&S) -> bool, visited: Visited)
+    -> Result<Option<Path<S, usize>>, Error>
+where S: Clone + Eq + Hash, I: IntoIterator<Item = S> {
+    let mut arena = Arena { nodes: vec![] };
+    let mut seen = HashSet::new();
+    if visited == Visited::Remember {
+        seen.insert(start.clone());
+    }
+    let mut queue = VecDeque::from([arena.push(start, None, 0)]);
+    while let Some(node) = queue.pop_front() {
+        utils::check_cancelled()?;
+        if is_goal(&arena.nodes[node].0) {
+            return Ok(Some(arena.path_to(node)));
+        }
+        let steps = arena.nodes[node].2 + 1;
+        for next in successors(&arena.nodes[node].0) {
+            if visited == Visited::Forget || seen.insert(next.clone()) {
+                queue.push_back(arena.push(next, Some(node), steps));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Finds a cheapest path from `start` to a state that `is_goal` accepts.
+/// `successors` yields the states reachable in one step, each with the non-negative cost of that step.
+pub fn dijkstra<S, C, I>(start: S, successors: impl FnMut(&S) -> I, is_goal: impl FnMut(&S) -> bool, visited: Visited)
+    -> Result<Option<Path<S, C>>, Error>
+where S: Clone + Eq + Hash, C: Coord, I: IntoIterator<Item = (S, C)> {
+    astar(start, successors, |_| C::ZERO, is_goal, visited)
+}
+
+/// Like [`dijkstra`], but expands the states that `heuristic` deems closest to a goal first.
+/// The path is the cheapest as long as `heuristic` never overestimates the remaining cost.
+pub fn astar<S, C, I>(start: S, mut successors: impl FnMut(&S) -> I, mut heuristic: impl FnMut(&S) -> C,
+                      mut is_goal: impl FnMut(&S) -> bool, visited: Visited)
+    -> Result<Option<Path<S, C>>, Error>
+where S: Clone + Eq + Hash, C: Coord, I: IntoIterator<Item = (S, C)> {
+    let mut arena = Arena { nodes: vec![] };
+    // The cheapest known cost of each state, so costlier ways to it are dropped.
+    let mut best = HashMap::new();
+    if visited == Visited::Remember {
+        best.insert(start.clone(), C::ZERO);
+    }
+    let mut heap = BinaryHeap::new();
+    let estimate = heuristic(&start);
+    // `Reverse` turns the max-heap into a min-heap; the node index breaks ties in insertion order.
+    heap.push(Reverse((estimate, arena.push(start, None, C::ZERO))));
+    while let Some(Reverse((_, node))) = heap.pop() {
+        utils::check_cancelled()?;
+        let (state, _, cost) = &arena.nodes[node];
+        if visited == Visited::Remember && best.get(state).is_some_and(|best| best < cost) {
+            continue;
+        }
+        if is_goal(state) {
+            return Ok(Some(arena.path_to(node)));
+        }
+        let cost = *cost;
+        for (next, step) in successors(state) {
+            let next_cost = cost + step;
+            if visited == Visited::Remember {
+                if best.get(&next).is_some_and(|&best| best <= next_cost) {
+                    continue;
+                }
+                best.insert(next.clone(), next_cost);
+            }
+            let estimate = next_cost + heuristic(&next);
+            heap.push(Reverse((estimate, arena.push(next, Some(node), next_cost))));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small weighted DAG where the cheapest way from `a` to `d` is not the one with the fewest steps.
+    fn edges(state: &char) -> Vec<(char, u32)> {
+        match state {
+            'a' => vec![('b', 1), ('c', 4)],
+            'b' => vec![('c', 2), ('d', 5)],
+            'c' => vec![('d', 1)],
+            _ => vec![]
+        }
+    }
+
+    /// The exact remaining cost, which never overestimates.
+    fn remaining(state: &char) -> u32 {
+        match state {
+            'a' => 4,
+            'b' => 3,
+            'c' => 1,
+            _ => 0
+        }
+    }
+
+    fn cheapest() -> Path<char, u32> {
+        Path { states: vec!['a', 'b', 'c', 'd'], cost: 4 }
+    }
+
+    #[test]
+    fn dijkstra_finds_the_cheapest_path() {
+        let path = dijkstra('a', edges, |&s| s == 'd', Visited::Remember).unwrap();
+        assert_eq!(path, Some(cheapest()));
+        assert_eq!(path.unwrap().goal(), &'d');
+    }
+
+    #[test]
+    fn astar_finds_the_cheapest_path() {
+        assert_eq!(astar('a', edges, remaining, |&s| s == 'd', Visited::Remember).unwrap(), Some(cheapest()));
+    }
+
+    #[test]
+    fn bfs_finds_the_fewest_steps() {
+        let path = bfs('a', |&s| edges(&s).into_iter().map(|(next, _)| next), |&s| s == 'd', Visited::Remember).unwrap();
+        assert_eq!(path, Some(Path { states: vec!['a', 'b', 'd'], cost: 2 }));
+    }
+
+    #[test]
+    fn forgetting_works_on_a_dag() {
+        assert_eq!(dijkstra('a', edges, |&s| s == 'd', Visited::Forget).unwrap(), Some(cheapest()));
+        assert_eq!(astar('a', edges, remaining, |&s| s == 'd', Visited::Forget).unwrap(), Some(cheapest()));
+        let steps = bfs('a', |&s| edges(&s).into_iter().map(|(next, _)| next), |&s| s == 'd', Visited::Forget).unwrap();
+        assert_eq!(steps.map(|p| p.cost), Some(2));
+    }
+
+    #[test]
+    fn unreachable_goals_have_no_path() {
+        assert_eq!(dijkstra('c', edges, |&s| s == 'a', Visited::Remember).unwrap(), None);
+        assert_eq!(bfs('d', |_| vec![], |&s| s == 'a', Visited::Forget).unwrap(), None);
+    }
+
+    #[test]
+    fn astar_costs_as_much_as_dijkstra() {
+        // A grid whose cells cost 1 to 9 to enter, from a xorshift generator so every run checks the same grids.
+        const SIZE: i32 = 12;
+        let mut seed = 0x2545_F491_4F6C_DD1Du64;
+        for _ in 0..20 {
+            let weights = (0..SIZE * SIZE).map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                (seed % 9 + 1) as i32
+            }).collect::<Vec<i32>>();
+            let successors = |&(x, y): &(i32, i32)| [(0, 1), (1, 0), (0, -1), (-1, 0)].into_iter()
+                .map(move |(dx, dy)| (x + dx, y + dy))
+                .filter(|&(x, y)| (0..SIZE).contains(&x) && (0..SIZE).contains(&y))
+                .map(|(x, y)| ((x, y), weights[(y * SIZE + x) as usize]))
+                .collect::<Vec<((i32, i32), i32)>>();
+            let goal = (SIZE - 1, SIZE - 1);
+            // Every step costs at least 1, so the Manhattan distance never overestimates.
+            let manhattan = |&(x, y): &(i32, i32)| (goal.0 - x) + (goal.1 - y);
+            let expected = dijkstra((0, 0), successors, |&s| s == goal, Visited::Remember).unwrap().unwrap();
+            let found = astar((0, 0), successors, manhattan, |&s| s == goal, Visited::Remember).unwrap().unwrap();
+            assert_eq!(found.cost, expected.cost);
+            let walked = found.states.windows(2).map(|w| weights[(w[1].1 * SIZE + w[1].0) as usize]).sum::<i32>();
+            assert_eq!(walked, found.cost);
+        }
+    }
+}
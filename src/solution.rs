@@ -0,0 +1,240 @@
+﻿use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Deserializer};
+use crate::input::InputSource;
+use crate::lint;
+use crate::memory;
+use crate::error::Error;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Part {
+    One, Two
+}
+
+impl Part {
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2
+        }
+    }
+}
+
+impl TryFrom<u8> for Part {
+    type Error = Error;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => Err(Error::Usage(format!("Invalid part: {}", value)))
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+/// Named knobs a solution reads besides its input, e.g. how many connections day 8 makes.
+#[derive(Clone, Default, Debug)]
+pub struct Params {
+    values: BTreeMap<String, String>
+}
+
+/// A parameter value as written in a TOML file, where `5` and `"5"` should mean the same.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ParamValue {
+    Text(String),
+    Integer(i64),
+    Float(f64),
+    Bool(bool)
+}
+
+impl<'de> Deserialize<'de> for Params {
+    /// Reads a table of strings, numbers and booleans, which [`Params::get`] parses like command line values.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let values = BTreeMap::<String, ParamValue>::deserialize(deserializer)?.into_iter()
+            .map(|(name, value)| (name, match value {
+                ParamValue::Text(text) => text,
+                ParamValue::Integer(n) => n.to_string(),
+                ParamValue::Float(x) => x.to_string(),
+                ParamValue::Bool(b) => b.to_string()
+            }))
+            .collect();
+        Ok(Params { values })
+    }
+}
+
+impl Params {
+    pub fn with(mut self, name: &str, value: impl ToString) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    pub fn set(&mut self, name: &str, value: impl ToString) {
+        self.values.insert(name.to_string(), value.to_string());
+    }
+
+    /// Overwrites every value that `other` defines.
+    pub fn merge(&mut self, other: &Params) {
+        for (name, value) in &other.values {
+            self.values.insert(name.clone(), value.clone());
+        }
+    }
+
+    pub fn get<T: FromStr>(&self, name: &str) -> Result<T, Error> {
+        let value = self.values.get(name)
+            .ok_or_else(|| Error::Usage(format!("Missing parameter '{}'", name)))?;
+        value.parse::<T>()
+            .map_err(|_| Error::Usage(format!("Invalid value for parameter '{}': {}", name, value)))
+    }
+}
+
+impl FromStr for Params {
+    type Err = Error;
+    /// Parses a comma-separated list of `name=value` pairs.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut params = Params::default();
+        for pair in s.split(',').filter(|p| !p.trim().is_empty()) {
+            let (name, value) = pair.split_once('=')
+                .ok_or_else(|| Error::Usage(format!("Expected name=value: {}", pair)))?;
+            params.set(name.trim(), value.trim());
+        }
+        Ok(params)
+    }
+}
+
+pub trait Solution {
+    type Input;
+
+    fn day(&self) -> u8;
+
+    fn default_params(&self, _source: &InputSource) -> Params {
+        Params::default()
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error>;
+
+    /// Problems in `input` that `aoc lint` reports, each at the line it is in. See [`crate::lint`] for the checks.
+    fn lint(&self, input: &str) -> Vec<Error> {
+        lint::whitespace(input)
+    }
+
+    fn part_1(&self, input: &Self::Input, params: &Params) -> Result<String, Error>;
+    fn part_2(&self, input: &Self::Input, params: &Params) -> Result<String, Error>;
+}
+
+pub struct Outcome {
+    pub answer: Result<String, Error>,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+    /// Peak heap growth while parsing and solving, if the tracking allocator is installed.
+    pub peak_memory: Option<usize>
+}
+
+/// Object-safe view of a [`Solution`], so days with different input types fit in one registry.
+pub trait Runner: Sync {
+    fn day(&self) -> u8;
+    fn default_params(&self, source: &InputSource) -> Params;
+    fn lint(&self, input: &str) -> Vec<Error>;
+    /// Parses `input` without solving anything, to find the first error the parser reports.
+    fn check_parse(&self, input: &str) -> Result<(), Error>;
+    fn run(&self, input: &str, part: Part, params: &Params) -> Outcome;
+}
+
+impl<S: Solution + Sync> Runner for S {
+    fn day(&self) -> u8 {
+        Solution::day(self)
+    }
+
+    fn default_params(&self, source: &InputSource) -> Params {
+        Solution::default_params(self, source)
+    }
+
+    fn lint(&self, input: &str) -> Vec<Error> {
+        Solution::lint(self, input)
+    }
+
+    fn check_parse(&self, input: &str) -> Result<(), Error> {
+        self.parse(input).map(|_| ())
+    }
+
+    fn run(&self, input: &str, part: Part, params: &Params) -> Outcome {
+        let baseline = memory::reset_peak();
+        let start = Instant::now();
+        let parsed = self.parse(input);
+        let parse_time = start.elapsed();
+        let parsed = match parsed {
+            Ok(parsed) => parsed,
+            Err(err) => return Outcome {
+                answer: Err(err),
+                parse_time,
+                solve_time: Duration::ZERO,
+                peak_memory: memory::peak_since(baseline)
+            }
+        };
+        let start = Instant::now();
+        let answer = match part {
+            Part::One => self.part_1(&parsed, params),
+            Part::Two => self.part_2(&parsed, params)
+        };
+        let solve_time = start.elapsed();
+        Outcome { answer, parse_time, solve_time, peak_memory: memory::peak_since(baseline) }
+    }
+}
+
+/// Every implemented day, in order. `aoc new` adds new days here.
+pub fn registry() -> Vec<&'static dyn Runner> {
+    vec![
+        &crate::day01::Day01,
+        &crate::day02::Day02,
+        &crate::day03::Day03,
+        &crate::day04::Day04,
+        &crate::day05::Day05,
+        &crate::day06::Day06,
+        &crate::day07::Day07,
+        &crate::day08::Day08,
+        &crate::day09::Day09,
+        &crate::day10::Day10,
+    ]
+}
+
+pub fn find(day: u8) -> Result<&'static dyn Runner, Error> {
+    registry().into_iter()
+        .find(|s| s.day() == day)
+        .ok_or_else(|| Error::Usage(format!("Day {} is not implemented", day)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params_accept_toml_scalars() {
+        let params = toml::from_str::<Params>("count = 5\nratio = 0.5\nverbose = true\nname = \"x\"").unwrap();
+        assert_eq!(params.get::<usize>("count").unwrap(), 5);
+        assert_eq!(params.get::<f64>("ratio").unwrap(), 0.5);
+        assert!(params.get::<bool>("verbose").unwrap());
+        assert_eq!(params.get::<String>("name").unwrap(), "x");
+        assert!(toml::from_str::<Params>("count = [1]").is_err());
+    }
+
+    #[test]
+    fn registry_days_are_unique_and_sorted() {
+        let days = registry().iter().map(|s| s.day()).collect::<Vec<u8>>();
+        assert!(days.windows(2).all(|w| w[0] < w[1]), "days not unique and sorted: {:?}", days);
+    }
+
+    #[test]
+    fn find_resolves_every_registered_day() {
+        for day in registry().iter().map(|s| s.day()) {
+            assert_eq!(find(day).map(|s| s.day()).ok(), Some(day));
+        }
+        assert!(matches!(find(0), Err(Error::Usage(_))));
+    }
+}
@@ -0,0 +1,339 @@
+﻿use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::fetch;
+use crate::http;
+use crate::http::HttpClient;
+use crate::solution::Part;
+use crate::utils;
+use crate::error::Error;
+
+pub static HISTORY: &str = "submissions.toml";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(from = "StoredVerdict", into = "StoredVerdict")]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint in which direction.
+    Wrong,
+    /// Rejected without being checked because the last submission was too recent,
+    /// with how many seconds are left if the page says so.
+    Wait { seconds: Option<u64> },
+    AlreadySolved,
+    Unrecognized
+}
+
+/// A [`Verdict`] as written to [`HISTORY`]. The wait is stored as [`Submission::retry_after`] instead.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum StoredVerdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    Wait,
+    AlreadySolved,
+    Unrecognized
+}
+
+impl From<StoredVerdict> for Verdict {
+    fn from(stored: StoredVerdict) -> Self {
+        match stored {
+            StoredVerdict::Correct => Verdict::Correct,
+            StoredVerdict::TooHigh => Verdict::TooHigh,
+            StoredVerdict::TooLow => Verdict::TooLow,
+            StoredVerdict::Wrong => Verdict::Wrong,
+            StoredVerdict::Wait => Verdict::Wait { seconds: None },
+            StoredVerdict::AlreadySolved => Verdict::AlreadySolved,
+            StoredVerdict::Unrecognized => Verdict::Unrecognized
+        }
+    }
+}
+
+impl From<Verdict> for StoredVerdict {
+    fn from(verdict: Verdict) -> Self {
+        match verdict {
+            Verdict::Correct => StoredVerdict::Correct,
+            Verdict::TooHigh => StoredVerdict::TooHigh,
+            Verdict::TooLow => StoredVerdict::TooLow,
+            Verdict::Wrong => StoredVerdict::Wrong,
+            Verdict::Wait { .. } => StoredVerdict::Wait,
+            Verdict::AlreadySolved => StoredVerdict::AlreadySolved,
+            Verdict::Unrecognized => StoredVerdict::Unrecognized
+        }
+    }
+}
+
+impl Verdict {
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "That's the right answer!"),
+            Verdict::TooHigh => write!(f, "Wrong: too high"),
+            Verdict::TooLow => write!(f, "Wrong: too low"),
+            Verdict::Wrong => write!(f, "Wrong"),
+            Verdict::Wait { seconds: Some(seconds) } => write!(f, "Submitted too recently: wait another {}s", seconds),
+            Verdict::Wait { seconds: None } => write!(f, "Submitted too recently"),
+            Verdict::AlreadySolved => write!(f, "Already solved"),
+            Verdict::Unrecognized => write!(f, "Unrecognized response")
+        }
+    }
+}
+
+lazy_static! {
+    static ref ARTICLE_REGEX: Regex = Regex::new(r"(?s)<article>(.*?)</article>").unwrap();
+    static ref TAG_REGEX: Regex = Regex::new(r"<[^>]*>").unwrap();
+    static ref LEFT_TO_WAIT_REGEX: Regex = Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait").unwrap();
+    static ref PLEASE_WAIT_REGEX: Regex = Regex::new(r"[Pp]lease wait (one|\d+) minutes?").unwrap();
+}
+
+/// Extracts the verdict and, if the page asks for one, the number of seconds to wait before the next submission.
+pub fn parse_response(html: &str) -> (Verdict, Option<u64>) {
+    let article = ARTICLE_REGEX.captures(html)
+        .map(|c| c[1].to_string())
+        .unwrap_or_else(|| html.to_string());
+    let text = TAG_REGEX.replace_all(&article, "");
+
+    let wait = if let Some(c) = LEFT_TO_WAIT_REGEX.captures(&text) {
+        let minutes = c.get(1).map_or(0, |m| m.as_str().parse::<u64>().unwrap_or(0));
+        let seconds = c[2].parse::<u64>().unwrap_or(0);
+        Some(minutes * 60 + seconds)
+    } else {
+        PLEASE_WAIT_REGEX.captures(&text).map(|c| match &c[1] {
+            "one" => 60,
+            n => n.parse::<u64>().unwrap_or(1) * 60
+        })
+    };
+    let verdict = if text.contains("That's the right answer") {
+        Verdict::Correct
+    } else if text.contains("your answer is too high") {
+        Verdict::TooHigh
+    } else if text.contains("your answer is too low") {
+        Verdict::TooLow
+    } else if text.contains("That's not the right answer") {
+        Verdict::Wrong
+    } else if text.contains("You gave an answer too recently") {
+        Verdict::Wait { seconds: wait }
+    } else if text.contains("You don't seem to be solving the right level") {
+        Verdict::AlreadySolved
+    } else {
+        Verdict::Unrecognized
+    };
+    (verdict, wait)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Submission {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+    /// Unix time of the submission.
+    pub time: u64,
+    /// Unix time before which the site will not accept another answer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<u64>
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct History {
+    #[serde(default, rename = "submission")]
+    submissions: Vec<Submission>
+}
+
+impl History {
+    pub fn load(path: &str) -> Result<History, Error> {
+        if !Path::new(path).exists() {
+            return Ok(History::default());
+        }
+        toml::from_str(&utils::read_file(path)?)
+            .map_err(|e| Error::store(format!("Failed to parse '{}'", path), e))
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        let content = toml::to_string(self)
+            .map_err(|e| Error::store("Failed to serialize submissions", e))?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Refuses answers that are known to be wrong, parts that are already solved and submissions inside a wait period.
+    pub fn check_allowed(&self, day: u8, part: Part, answer: &str, now: u64) -> Result<(), Error> {
+        if let Some(retry_after) = self.submissions.iter().filter_map(|s| s.retry_after).max()
+            && retry_after > now {
+            return Err(Error::Failed(format!("Rate limited: wait another {}s before submitting", retry_after - now)));
+        }
+        let numeric = answer.parse::<i64>().ok();
+        for s in self.submissions.iter().filter(|s| s.day == day && s.part == part.number()) {
+            if s.verdict == Verdict::Correct {
+                return Err(Error::Failed(format!("Day {} part {} is already solved with {}", day, part, s.answer)));
+            }
+            if s.verdict.is_wrong() && s.answer == answer {
+                return Err(Error::Failed(format!("{} was already submitted: {}", answer, s.verdict)));
+            }
+            if let (Some(new), Ok(old)) = (numeric, s.answer.parse::<i64>()) {
+                if s.verdict == Verdict::TooHigh && new >= old {
+                    return Err(Error::Failed(format!("{} cannot be right: {} was already too high", answer, old)));
+                }
+                if s.verdict == Verdict::TooLow && new <= old {
+                    return Err(Error::Failed(format!("{} cannot be right: {} was already too low", answer, old)));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Posts `answer` unless the history already rules it out, and records the verdict in `history`.
+pub fn submit(client: &dyn HttpClient, base_url: &str, token: &str, history: &mut History, day: u8, part: Part, answer: &str)
+    -> Result<Verdict, Error> {
+    let now = unix_now();
+    history.check_allowed(day, part, answer, now)?;
+
+    let url = format!("{}/{}/day/{}/answer", base_url, fetch::YEAR, day);
+    let cookie = format!("session={}", token);
+    let level = part.number().to_string();
+    let response = client.post_form(&url, &http::session_headers(&cookie), &[("level", &level), ("answer", answer)])?;
+    if response.status != 200 {
+        return Err(Error::network(format!("Submitting to {} failed with status {}: {}", url, response.status, response.body.trim())));
+    }
+
+    let (verdict, wait) = parse_response(&response.body);
+    history.submissions.push(Submission {
+        day,
+        part: part.number(),
+        answer: answer.to_string(),
+        verdict,
+        time: now,
+        retry_after: wait.map(|w| now + w)
+    });
+    Ok(verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::http::fake::FakeClient;
+    use crate::http::USER_AGENT;
+    use super::*;
+
+    fn page(text: &str) -> String {
+        format!("<html><body><main><article><p>{}</p></article></main></body></html>", text)
+    }
+
+    fn submission(answer: &str, verdict: Verdict) -> Submission {
+        Submission { day: 1, part: 1, answer: answer.to_string(), verdict, time: 0, retry_after: None }
+    }
+
+    #[test]
+    fn parses_every_verdict() {
+        let cases = [
+            ("That's the right answer! You are one gold star closer.", Verdict::Correct),
+            ("That's not the right answer; your answer is too high.", Verdict::TooHigh),
+            ("That's not the right answer; your answer is too low.", Verdict::TooLow),
+            ("That's not the right answer. If you're stuck, make sure you're using the full input data.", Verdict::Wrong),
+            ("You gave an answer too recently; you have to wait after submitting an answer before trying again.", Verdict::Wait { seconds: None }),
+            ("You gave an answer too recently. You have 4m 32s left to wait.", Verdict::Wait { seconds: Some(272) }),
+            ("You don't seem to be solving the right level. Did you already complete it?", Verdict::AlreadySolved),
+            ("Something else entirely.", Verdict::Unrecognized)
+        ];
+        for (text, verdict) in cases {
+            assert_eq!(parse_response(&page(text)).0, verdict, "{}", text);
+        }
+    }
+
+    #[test]
+    fn parses_wait_times() {
+        let wait = |text: &str| parse_response(&page(text)).1;
+        assert_eq!(wait("You gave an answer too recently. You have 4m 32s left to wait."), Some(272));
+        assert_eq!(wait("You gave an answer too recently. You have 45s left to wait."), Some(45));
+        assert_eq!(wait("That's not the right answer. Please wait one minute before trying again."), Some(60));
+        assert_eq!(wait("That's not the right answer. Please wait 5 minutes before trying again."), Some(300));
+        assert_eq!(wait("That's the right answer!"), None);
+    }
+
+    #[test]
+    fn refuses_answers_known_to_be_wrong() {
+        let history = History { submissions: vec![
+            submission("100", Verdict::TooHigh),
+            submission("10", Verdict::TooLow),
+            submission("50", Verdict::Wrong)
+        ] };
+        assert!(history.check_allowed(1, Part::One, "50", 0).is_err());
+        assert!(history.check_allowed(1, Part::One, "100", 0).is_err());
+        assert!(history.check_allowed(1, Part::One, "150", 0).is_err());
+        assert!(history.check_allowed(1, Part::One, "10", 0).is_err());
+        assert!(history.check_allowed(1, Part::One, "5", 0).is_err());
+        assert!(history.check_allowed(1, Part::One, "42", 0).is_ok());
+        assert!(history.check_allowed(1, Part::Two, "50", 0).is_ok());
+    }
+
+    #[test]
+    fn refuses_solved_parts_and_waits() {
+        let solved = History { submissions: vec![submission("42", Verdict::Correct)] };
+        assert!(solved.check_allowed(1, Part::One, "43", 0).is_err());
+
+        let waiting = History { submissions: vec![Submission { retry_after: Some(100), ..submission("1", Verdict::Wrong) }] };
+        assert!(waiting.check_allowed(2, Part::One, "2", 99).is_err());
+        assert!(waiting.check_allowed(2, Part::One, "2", 100).is_ok());
+    }
+
+    #[test]
+    fn submits_with_session_and_records_the_verdict() {
+        let client = FakeClient::new(200, &page("That's not the right answer; your answer is too low. Please wait one minute."));
+        let mut history = History::default();
+        let verdict = submit(&client, "http://aoc.test", "secret", &mut history, 3, Part::Two, "17").unwrap();
+        assert_eq!(verdict, Verdict::TooLow);
+
+        let requests = client.requests.borrow();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].url, format!("http://aoc.test/{}/day/3/answer", fetch::YEAR));
+        assert_eq!(requests[0].header("Cookie"), Some("session=secret"));
+        assert_eq!(requests[0].header("User-Agent"), Some(USER_AGENT));
+        assert_eq!(requests[0].form, [("level".to_string(), "2".to_string()), ("answer".to_string(), "17".to_string())]);
+
+        let recorded = &history.submissions[0];
+        assert_eq!((recorded.day, recorded.part, recorded.answer.as_str(), recorded.verdict), (3, 2, "17", Verdict::TooLow));
+        assert_eq!(recorded.retry_after.map(|r| r - recorded.time), Some(60));
+    }
+
+    #[test]
+    fn submits_too_early_and_records_the_wait() {
+        let client = FakeClient::new(200, &page("You gave an answer too recently. You have 1m 5s left to wait."));
+        let mut history = History::default();
+        let verdict = submit(&client, "http://aoc.test", "secret", &mut history, 3, Part::One, "17").unwrap();
+        assert_eq!(verdict, Verdict::Wait { seconds: Some(65) });
+        assert_eq!(verdict.to_string(), "Submitted too recently: wait another 65s");
+
+        let recorded = &history.submissions[0];
+        assert_eq!(recorded.retry_after.map(|r| r - recorded.time), Some(65));
+        let stored = toml::to_string(&history).unwrap();
+        assert!(stored.contains("verdict = \"wait\""), "{}", stored);
+        let loaded = toml::from_str::<History>(&stored).unwrap();
+        assert_eq!(loaded.submissions[0].verdict, Verdict::Wait { seconds: None });
+        assert!(loaded.check_allowed(3, Part::One, "18", recorded.time + 64).is_err());
+    }
+
+    #[test]
+    fn refused_answers_are_not_sent() {
+        let client = FakeClient::new(200, &page("That's the right answer!"));
+        let mut history = History { submissions: vec![submission("50", Verdict::Wrong)] };
+        assert!(submit(&client, "http://aoc.test", "secret", &mut history, 1, Part::One, "50").is_err());
+        assert!(client.requests.borrow().is_empty());
+        assert_eq!(history.submissions.len(), 1);
+    }
+}
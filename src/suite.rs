@@ -0,0 +1,359 @@
+﻿use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex, PoisonError};
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
+use std::time::Duration;
+use serde::Serialize;
+use crate::input::InputSource;
+use crate::solution::{Outcome, Params, Part, Runner};
+use crate::utils;
+use crate::error::Error;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InputKind {
+    Sample, Actual
+}
+
+impl InputKind {
+    pub fn source(&self) -> InputSource {
+        match self {
+            InputKind::Sample => InputSource::Sample(None),
+            InputKind::Actual => InputSource::Actual
+        }
+    }
+
+    /// The kind `source` stands for, if it is the default sample or the actual input.
+    pub fn of(source: &InputSource) -> Option<InputKind> {
+        match source {
+            InputSource::Sample(None) => Some(InputKind::Sample),
+            InputSource::Actual => Some(InputKind::Actual),
+            _ => None
+        }
+    }
+}
+
+impl Display for InputKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputKind::Sample => write!(f, "sample"),
+            InputKind::Actual => write!(f, "actual")
+        }
+    }
+}
+
+pub enum Status {
+    Ok,
+    Failed(Error),
+    NoInput(Error),
+    /// Still running when the timeout ran out, so it was cancelled.
+    TimedOut(Duration)
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Ok => write!(f, "ok"),
+            Status::Failed(_) => write!(f, "error"),
+            Status::NoInput(_) => write!(f, "no input"),
+            Status::TimedOut(_) => write!(f, "timed out")
+        }
+    }
+}
+
+pub struct Record {
+    pub day: u8,
+    pub part: Part,
+    pub source: InputSource,
+    pub answer: Option<String>,
+    pub status: Status,
+    pub input_hash: Option<String>,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+    /// None if nothing was measured, or if other runs allocated at the same time and the measurement would be off.
+    pub peak_memory: Option<usize>
+}
+
+impl Record {
+    fn no_input(solution: &dyn Runner, part: Part, source: &InputSource, err: Error) -> Record {
+        Record {
+            day: solution.day(),
+            part,
+            source: source.clone(),
+            answer: None,
+            status: Status::NoInput(err),
+            input_hash: None,
+            parse_time: Duration::ZERO,
+            solve_time: Duration::ZERO,
+            peak_memory: None
+        }
+    }
+
+    /// The answer, or the message of what went wrong.
+    pub fn result(&self) -> Result<String, String> {
+        match &self.status {
+            Status::Ok => Ok(self.answer.clone().unwrap_or_default()),
+            Status::Failed(err) | Status::NoInput(err) => Err(err.to_string()),
+            Status::TimedOut(timeout) => Err(format!("Timed out after {}", format_duration(*timeout)))
+        }
+    }
+
+    /// What went wrong, if it was an error rather than a timeout.
+    pub fn error(&self) -> Option<&Error> {
+        match &self.status {
+            Status::Failed(err) | Status::NoInput(err) => Some(err),
+            Status::Ok | Status::TimedOut(_) => None
+        }
+    }
+}
+
+/// Stack size of the worker threads solutions run on, matching the main thread's so recursive solutions behave the same.
+const WORKER_STACK_SIZE: usize = 8 * 1024 * 1024;
+/// How long a cancelled solution gets to notice before the suite moves on without it.
+const CANCEL_GRACE: Duration = Duration::from_secs(1);
+
+/// Runs `part` on a worker thread and gives up on it after `timeout`. The worker is cancelled via
+/// [`utils::check_cancelled`], so solutions that never check keep running in the background.
+fn run_with_timeout(solution: &'static dyn Runner, input: String, part: Part, params: Params, timeout: Duration) -> Result<Outcome, Status> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let flag = cancel.clone();
+    let (sender, receiver) = mpsc::channel();
+    let worker = thread::Builder::new()
+        .name(format!("day{:02}-part{}", solution.day(), part))
+        .stack_size(WORKER_STACK_SIZE)
+        .spawn(move || {
+            utils::set_cancel_flag(flag);
+            let _ = sender.send(solution.run(&input, part, &params));
+        });
+    if let Err(err) = worker {
+        return Err(Status::Failed(err.into()));
+    }
+    match receiver.recv_timeout(timeout) {
+        Ok(outcome) => Ok(outcome),
+        Err(RecvTimeoutError::Timeout) => {
+            cancel.store(true, Ordering::Relaxed);
+            let _ = receiver.recv_timeout(CANCEL_GRACE);
+            Err(Status::TimedOut(timeout))
+        },
+        Err(RecvTimeoutError::Disconnected) => Err(Status::Failed(Error::Failed("Solution panicked".to_string())))
+    }
+}
+
+/// Runs one part on `source` with the solution's default parameters, overwritten by `overrides`.
+/// With a `timeout`, the solution runs on its own thread and is reported as [`Status::TimedOut`] if it takes longer.
+pub fn run_one(solution: &'static dyn Runner, part: Part, source: &InputSource, overrides: &Params, timeout: Option<Duration>) -> Record {
+    let input = match source.read(solution.day()) {
+        Ok(input) => input,
+        Err(err) => return Record::no_input(solution, part, source, err)
+    };
+    let input_hash = Some(utils::hash_hex(&input));
+    let mut params = solution.default_params(source);
+    params.merge(&source.params(solution.day()));
+    params.merge(overrides);
+    let outcome = match timeout {
+        Some(timeout) => run_with_timeout(solution, input, part, params, timeout),
+        None => Ok(solution.run(&input, part, &params))
+    };
+    let outcome = match outcome {
+        Ok(outcome) => outcome,
+        Err(status) => return Record {
+            day: solution.day(),
+            part,
+            source: source.clone(),
+            answer: None,
+            solve_time: timeout.filter(|_| matches!(status, Status::TimedOut(_))).unwrap_or_default(),
+            status,
+            input_hash,
+            parse_time: Duration::ZERO,
+            peak_memory: None
+        }
+    };
+    let (answer, status) = match outcome.answer {
+        Ok(answer) => (Some(answer), Status::Ok),
+        Err(err) => (None, Status::Failed(err.in_file(&source.path(solution.day()))))
+    };
+    Record {
+        day: solution.day(),
+        part,
+        source: source.clone(),
+        answer,
+        status,
+        input_hash,
+        parse_time: outcome.parse_time,
+        solve_time: outcome.solve_time,
+        peak_memory: outcome.peak_memory
+    }
+}
+
+/// Runs both parts of every solution on every kind of input, on up to `jobs` threads at once.
+/// The records come back in the same order either way. With more than one job they have no peak memory,
+/// since the jobs share one allocator and would reset each other's measurements.
+pub fn run_all(solutions: &[&'static dyn Runner], kinds: &[InputKind], timeout: Option<Duration>, jobs: usize) -> Vec<Record> {
+    let mut tasks = vec![];
+    for &solution in solutions {
+        for part in [Part::One, Part::Two] {
+            for &kind in kinds {
+                tasks.push((solution, part, kind));
+            }
+        }
+    }
+    let run_task = |&(solution, part, kind): &(&'static dyn Runner, Part, InputKind)|
+        run_one(solution, part, &kind.source(), &Params::default(), timeout);
+    if jobs <= 1 {
+        return tasks.iter().map(run_task).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let finished = Mutex::new(Vec::with_capacity(tasks.len()));
+    let work = || loop {
+        let index = next.fetch_add(1, Ordering::Relaxed);
+        let Some(task) = tasks.get(index) else { break };
+        let record = run_task(task);
+        let record = Record { peak_memory: None, ..record };
+        finished.lock().unwrap_or_else(PoisonError::into_inner).push((index, record));
+    };
+    thread::scope(|scope| {
+        for i in 0..jobs.min(tasks.len()) {
+            let worker = thread::Builder::new()
+                .name(format!("worker-{}", i))
+                .stack_size(WORKER_STACK_SIZE)
+                .spawn_scoped(scope, work);
+            if worker.is_err() {
+                break;
+            }
+        }
+    });
+    // Picks up whatever is left if no worker could be started.
+    work();
+    let mut finished = finished.into_inner().unwrap_or_else(PoisonError::into_inner);
+    finished.sort_by_key(|(index, _)| *index);
+    finished.into_iter().map(|(_, record)| record).collect()
+}
+
+/// The machine-readable form of a [`Record`], printed by `--format json`.
+#[derive(Serialize)]
+pub struct Report {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub status: String,
+    pub answer: Option<String>,
+    pub error: Option<String>,
+    pub input_hash: Option<String>,
+    pub parse_ms: f64,
+    pub solve_ms: f64,
+    pub elapsed_ms: f64,
+    pub peak_memory_bytes: Option<usize>
+}
+
+impl From<&Record> for Report {
+    fn from(r: &Record) -> Self {
+        let error = r.result().err();
+        let millis = |d: Duration| d.as_secs_f64() * 1e3;
+        Report {
+            day: r.day,
+            part: r.part.number(),
+            input: r.source.to_string(),
+            status: r.status.to_string(),
+            answer: r.answer.clone(),
+            error,
+            input_hash: r.input_hash.clone(),
+            parse_ms: millis(r.parse_time),
+            solve_ms: millis(r.solve_time),
+            elapsed_ms: millis(r.parse_time + r.solve_time),
+            peak_memory_bytes: r.peak_memory
+        }
+    }
+}
+
+/// Prints `value` as a single line of JSON.
+pub fn print_json<T: Serialize>(value: &T) {
+    match serde_json::to_string(value) {
+        Ok(line) => println!("{}", line),
+        Err(err) => eprintln!("Failed to serialize report: {}", err)
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.2}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", nanos as f64 / 1e9)
+    }
+}
+
+pub fn truncate(s: &str, max_len: usize) -> String {
+    if s.chars().count() <= max_len {
+        s.to_string()
+    } else {
+        format!("{}…", s.chars().take(max_len - 1).collect::<String>())
+    }
+}
+
+pub fn print_table(records: &[Record]) {
+    let header = ["Day", "Part", "Input", "Answer", "Parse", "Solve", "Status"];
+    let rows = records.iter().map(|r| {
+        let answer = match &r.status {
+            Status::Failed(err) => truncate(&err.to_string(), 40),
+            Status::NoInput(_) | Status::TimedOut(_) => "-".to_string(),
+            Status::Ok => r.answer.clone().unwrap_or_default()
+        };
+        [
+            r.day.to_string(),
+            r.part.to_string(),
+            r.source.to_string(),
+            answer,
+            format_duration(r.parse_time),
+            format_duration(r.solve_time),
+            r.status.to_string()
+        ]
+    }).collect::<Vec<[String; 7]>>();
+
+    let passed = records.iter().filter(|r| matches!(r.status, Status::Ok)).count();
+    let total = [
+        "Total".to_string(),
+        String::new(),
+        String::new(),
+        String::new(),
+        format_duration(records.iter().map(|r| r.parse_time).sum()),
+        format_duration(records.iter().map(|r| r.solve_time).sum()),
+        format!("{}/{} ok", passed, records.len())
+    ];
+    print_rows(header, &rows, Some(total));
+}
+
+/// Prints an aligned table, with `footer` set apart below a separator.
+pub fn print_rows<const N: usize>(header: [&str; N], rows: &[[String; N]], footer: Option<[String; N]>) {
+    let mut widths = header.map(|h| h.chars().count());
+    for row in rows.iter().chain(&footer) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let format_row = |cells: &[String]| cells.iter().zip(widths)
+        .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+        .collect::<Vec<String>>()
+        .join(" | ")
+        .trim_end()
+        .to_string();
+    let separator = widths.iter()
+        .map(|&w| "-".repeat(w))
+        .collect::<Vec<String>>()
+        .join("-+-");
+
+    println!("{}", format_row(&header.map(String::from)));
+    println!("{}", separator);
+    for row in rows {
+        println!("{}", format_row(row));
+    }
+    if let Some(footer) = footer {
+        println!("{}", separator);
+        println!("{}", format_row(&footer));
+    }
+}
\ No newline at end of file
@@ -1,8 +1,19 @@
-﻿use std::{fs, io};
+﻿pub mod grid;
+pub mod interval;
+pub mod num;
+pub mod union_find;
+
+use std::{fs, io, iter};
+use std::borrow::Cow;
+use std::cell::RefCell;
 use std::fs::File;
 use std::io::BufRead;
-use std::num::ParseIntError;
+use std::ops::{Deref, Range};
 use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::error::Error;
 
 pub fn read_file(file_path: &str) -> io::Result<String> {
     fs::read_to_string(file_path)
@@ -21,44 +32,200 @@ where P: AsRef<Path>, {
     Ok(io::BufReader::new(file).lines())
 }
 
-pub(crate) struct ErrorMsg {
-    pub(crate) wrapped: String
+/// 64-bit FNV-1a hash of `text` in hex, stable across runs and platforms.
+pub fn hash_hex(text: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in text.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
 }
 
-impl ErrorMsg {
-    pub(crate) fn print(result: Result<(), ErrorMsg>) -> () {
-        result.unwrap_or_else(|err| println!("Error: {}", err.wrapped))
+/// The lines of `input` without a leading byte order mark, split at LF, CRLF and lone CR.
+/// Like `split('\n')`, ends with an empty line if the input ends with a line break.
+fn split_lines(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = Some(input.strip_prefix('\u{feff}').unwrap_or(input));
+    iter::from_fn(move || {
+        let text = rest?;
+        match text.find(['\r', '\n']) {
+            Some(end) => {
+                let next = if text[end..].starts_with("\r\n") { end + 2 } else { end + 1 };
+                rest = Some(&text[next..]);
+                Some(&text[..end])
+            },
+            None => {
+                rest = None;
+                Some(text)
+            }
+        }
+    })
+}
+
+/// Strips a leading byte order mark and turns CRLF and lone CR line endings into LF,
+/// so inputs saved on any platform parse the same.
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    if input.contains('\r') {
+        Cow::Owned(split_lines(input).collect::<Vec<&str>>().join("\n"))
+    } else {
+        Cow::Borrowed(input.strip_prefix('\u{feff}').unwrap_or(input))
     }
-    pub(crate) fn result_to_string(result: Result<String, ErrorMsg>) -> String {
-        result.unwrap_or_else(|err| err.wrapped)
+}
+
+/// A line of the puzzle input together with its 1-based number, so errors can point at it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str
+}
+
+impl Line<'_> {
+    /// Parses the line without surrounding whitespace.
+    pub fn parse<T>(&self) -> Result<T, Error> where T: FromStr, T::Err: Into<Error> {
+        let start = self.text.len() - self.text.trim_start().len();
+        self.parse_span(start..start + self.text.trim().len())
     }
-    pub(crate) fn new(string: &str) -> ErrorMsg {
-        ErrorMsg { wrapped: string.to_string() }
+
+    /// Parses every non-empty piece of the line between `separator`s, without surrounding whitespace.
+    pub fn parse_split<T>(&self, separator: char) -> Result<Vec<T>, Error> where T: FromStr, T::Err: Into<Error> {
+        let mut start = 0;
+        let mut values = vec![];
+        for piece in self.text.split(separator) {
+            let offset = start + piece.len() - piece.trim_start().len();
+            start += piece.len() + separator.len_utf8();
+            if !piece.trim().is_empty() {
+                values.push(self.parse_span(offset..offset + piece.trim().len())?);
+            }
+        }
+        Ok(values)
+    }
+
+    /// Places `err` on this line, underlining all of it.
+    pub fn error(&self, err: Error) -> Error {
+        err.at_line(self.number, self.text, 0..self.text.trim_end().len())
+    }
+
+    fn parse_span<T>(&self, span: Range<usize>) -> Result<T, Error> where T: FromStr, T::Err: Into<Error> {
+        self.text[span.clone()].parse::<T>().map_err(|err| err.into().at_line(self.number, self.text, span))
     }
 }
 
-impl From<io::Error> for ErrorMsg {
-    fn from(err: io::Error) -> Self {
-        ErrorMsg { wrapped: format!("IO error: {}", err.to_string()) }
+impl Deref for Line<'_> {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.text
     }
 }
-impl From<ParseIntError> for ErrorMsg {
-    fn from(err: ParseIntError) -> Self {
-        ErrorMsg { wrapped: format!("ParseIntError: {}", err.to_string()) }
+
+/// The lines of `input`, with line endings and a byte order mark removed and blank lines around them dropped.
+/// Fails on empty input and on blank lines in between, which only [`sections`] expects.
+pub fn lines(input: &str) -> Result<Vec<Line<'_>>, Error> {
+    let mut sections = sections(input)?;
+    if sections.len() > 1 {
+        let first = sections[1][0];
+        return Err(Error::Parse("Unexpected blank line".to_string())
+            .at_line(first.number - 1, "", 0..0));
     }
+    Ok(sections.remove(0))
 }
-impl From<String> for ErrorMsg {
-    fn from(err: String) -> Self {
-        ErrorMsg { wrapped: err }
+
+/// The blocks of lines of `input` that blank lines separate, cleaned up like [`lines`]. Fails on empty input.
+pub fn sections(input: &str) -> Result<Vec<Vec<Line<'_>>>, Error> {
+    let mut sections = vec![];
+    let mut section = vec![];
+    // Splits the way `normalize` does, so line numbers agree with the normalized input.
+    for (i, text) in split_lines(input).enumerate() {
+        if text.trim().is_empty() {
+            if !section.is_empty() {
+                sections.push(std::mem::take(&mut section));
+            }
+        } else {
+            section.push(Line { number: i + 1, text });
+        }
     }
+    if !section.is_empty() {
+        sections.push(section);
+    }
+    if sections.is_empty() {
+        return Err(Error::Parse("The input is empty".to_string()));
+    }
+    Ok(sections)
+}
+
+/// Parses every line of `input` on its own, without surrounding whitespace.
+/// Errors point at the line they happened in.
+pub fn parse_lines<T: FromStr<Err = Error>>(input: &str) -> Result<Vec<T>, Error> {
+    lines(input)?.iter().map(Line::parse).collect()
 }
-impl From<&str> for ErrorMsg {
-    fn from(err: &str) -> Self {
-        ErrorMsg { wrapped: err.to_string() }
+
+thread_local! {
+    static CANCEL_FLAG: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+/// Makes `flag` the cancellation flag that [`check_cancelled`] reads on the current thread.
+pub fn set_cancel_flag(flag: Arc<AtomicBool>) {
+    CANCEL_FLAG.with(|f| *f.borrow_mut() = Some(flag));
+}
+
+/// Fails once the current thread's run has been cancelled, e.g. because it timed out.
+/// Long-running solutions call this in their hot loops so they can be stopped cooperatively.
+pub fn check_cancelled() -> Result<(), Error> {
+    let cancelled = CANCEL_FLAG.with(|f| f.borrow().as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed)));
+    if cancelled {
+        Err(Error::Cancelled)
+    } else {
+        Ok(())
     }
 }
-impl From<regex::Error> for ErrorMsg {
-    fn from(err: regex::Error) -> Self {
-        ErrorMsg { wrapped: format!("Failed to compile regex: {}", err.to_string()) }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered<'a>(lines: &[Line<'a>]) -> Vec<(usize, &'a str)> {
+        lines.iter().map(|line| (line.number, line.text)).collect()
+    }
+
+    #[test]
+    fn normalize_unifies_line_endings() {
+        assert_eq!(normalize("a\r\nb\rc\n"), "a\nb\nc\n");
+        assert_eq!(normalize("\u{feff}a\nb"), "a\nb");
+        assert_eq!(normalize("\u{feff}a\r\n\r\n"), "a\n\n");
+        assert!(matches!(normalize("a\nb\n"), Cow::Borrowed(_)));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn lines_are_numbered_from_one() {
+        let input = "\u{feff}\r\n12\r\n 34 \r\n\r\n\r\n";
+        assert_eq!(numbered(&lines(input).unwrap()), [(2, "12"), (3, " 34 ")]);
+        assert_eq!(numbered(&lines("a\rb\r").unwrap()), [(1, "a"), (2, "b")]);
+    }
+
+    #[test]
+    fn lines_reject_blank_lines_in_between() {
+        let err = lines("1\r\n\r\n2").unwrap_err();
+        assert_eq!(err.location().and_then(|l| l.line), Some(2));
+        assert!(lines("\n \r\n").is_err());
+    }
+
+    #[test]
+    fn sections_split_at_blank_lines() {
+        for input in ["a\nb\n\nc\n\n", "a\r\nb\r\n\r\nc\r\n\r\n", "a\rb\r\rc\r\r", "\u{feff}a\nb\r\n\rc"] {
+            let sections = sections(input).unwrap();
+            let sections = sections.iter().map(|s| numbered(s)).collect::<Vec<Vec<(usize, &str)>>>();
+            assert_eq!(sections, [vec![(1, "a"), (2, "b")], vec![(4, "c")]], "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn lines_agree_with_the_normalized_input() {
+        let input = "x\ry\r\nz\n\rw";
+        let normalized = normalize(input);
+        let expected = normalized.split('\n').enumerate()
+            .filter(|(_, text)| !text.is_empty())
+            .map(|(i, text)| (i + 1, text))
+            .collect::<Vec<(usize, &str)>>();
+        let sections = sections(input).unwrap();
+        assert_eq!(sections.iter().flat_map(|s| numbered(s)).collect::<Vec<(usize, &str)>>(), expected);
+    }
+}
@@ -0,0 +1,251 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+use crate::utils;
+use crate::error::Error;
+
+/// A rectangle of cells, stored row by row in one `Vec`. Positions are `(row, column)`, starting at the top left.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize
+}
+
+const OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+const OFFSETS_8: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, 1), (1, 1), (1, 0), (1, -1), (0, -1)];
+
+impl<T> Grid<T> {
+    /// Fails if the rows are not all as long as the first one.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, Error> {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (i, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(Error::Parse(format!("Row {} has {} columns, but row 1 has {}", i + 1, row.len(), width)));
+            }
+            cells.extend(row);
+        }
+        Ok(Grid { cells, width, height })
+    }
+
+    /// Parses a map with one character per cell, converting each with `cell`.
+    /// Ignores whitespace at the end of lines. Errors point at the offending character or row.
+    pub fn parse(input: &str, cell: impl Fn(char) -> Result<T, Error>) -> Result<Self, Error> {
+        let lines = utils::lines(input)?;
+        let width = lines[0].trim_end().chars().count();
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for line in &lines {
+            let text = line.trim_end();
+            for (i, c) in text.char_indices() {
+                cells.push(cell(c).map_err(|err| err.at_line(line.number, line.text, i..i + c.len_utf8()))?);
+            }
+            let columns = text.chars().count();
+            if columns != width {
+                let message = format!("Row has {} columns, but line {} has {}", columns, lines[0].number, width);
+                return Err(line.error(Error::Parse(message)));
+            }
+        }
+        Ok(Grid { cells, width, height: lines.len() })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        (row < self.height && col < self.width).then(|| &self.cells[row * self.width + col])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        (row < self.height && col < self.width).then(|| &mut self.cells[row * self.width + col])
+    }
+
+    /// The cell at `row` and `col` as if the grid repeated endlessly in every direction.
+    /// None only if the grid has no cells to repeat.
+    pub fn get_wrapping(&self, row: isize, col: isize) -> Option<&T> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        let row = row.rem_euclid(self.height as isize) as usize;
+        let col = col.rem_euclid(self.width as isize) as usize;
+        self.get(row, col)
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// The positions above, right of, below and left of `(row, col)` that are inside the grid.
+    pub fn neighbours_4(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        self.offset_positions(row, col, &OFFSETS_4)
+    }
+
+    /// Like [`Grid::neighbours_4`], plus the diagonal ones.
+    pub fn neighbours_8(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        self.offset_positions(row, col, &OFFSETS_8)
+    }
+
+    fn offset_positions(&self, row: usize, col: usize, offsets: &'static [(isize, isize)]) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().filter_map(move |&(dr, dc)| {
+            let row = row.checked_add_signed(dr).filter(|&r| r < height)?;
+            let col = col.checked_add_signed(dc).filter(|&c| c < width)?;
+            Some((row, col))
+        })
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `max(1)` keeps `chunks` from panicking on a grid without columns.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.width.max(1)).take(self.height)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// The grid mirrored along its main diagonal, so its columns become rows.
+    pub fn transposed(&self) -> Grid<T> where T: Clone {
+        let cells = self.columns().flatten().cloned().collect();
+        Grid { cells, width: self.height, height: self.width }
+    }
+
+    /// The position of the first cell, row by row, that equals `value`.
+    pub fn find(&self, value: &T) -> Option<(usize, usize)> where T: PartialEq {
+        self.cells.iter().position(|cell| cell == value).map(|i| (i / self.width, i % self.width))
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid { cells: self.cells.iter().map(f).collect(), width: self.width, height: self.height }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        self.get(row, col).unwrap_or_else(|| panic!("({}, {}) is outside the {}x{} grid", row, col, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(row, col).unwrap_or_else(|| panic!("({}, {}) is outside the {}x{} grid", row, col, width, height))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::parse(s, Ok)
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    /// One line per row, with the cells written next to each other.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        "abc\ndef".parse().unwrap()
+    }
+
+    fn sorted(positions: impl Iterator<Item = (usize, usize)>) -> Vec<(usize, usize)> {
+        let mut positions = positions.collect::<Vec<(usize, usize)>>();
+        positions.sort();
+        positions
+    }
+
+    #[test]
+    fn parse_reads_rows_of_characters() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.to_string(), "abc\ndef");
+        assert_eq!(Grid::parse("12\n34  \n", |c| c.to_digit(10).ok_or_else(|| Error::Parse("no digit".to_string())))
+            .unwrap().row(1), [3, 4]);
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_problem() {
+        let ragged = "abc\nde\nfgh".parse::<Grid<char>>().unwrap_err();
+        assert_eq!(ragged.location().and_then(|l| l.line), Some(2));
+        let bad_cell = Grid::parse("..\n.x", |c| if c == '.' { Ok(()) } else { Err(Error::Parse("bad".to_string())) })
+            .unwrap_err();
+        let location = bad_cell.location().unwrap();
+        assert_eq!((location.line, location.column()), (Some(2), 2));
+        assert!("".parse::<Grid<char>>().is_err());
+        assert!(Grid::from_rows(vec![vec![1, 2], vec![3]]).is_err());
+    }
+
+    #[test]
+    fn get_is_none_outside() {
+        let grid = grid();
+        assert_eq!(grid.get(0, 0), Some(&'a'));
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.get(0, 3), None);
+        assert_eq!(grid.get(usize::MAX, usize::MAX), None);
+    }
+
+    #[test]
+    fn neighbours_stay_inside() {
+        let grid = grid();
+        assert_eq!(sorted(grid.neighbours_4(0, 0)), [(0, 1), (1, 0)]);
+        assert_eq!(sorted(grid.neighbours_4(1, 1)), [(0, 1), (1, 0), (1, 2)]);
+        assert_eq!(sorted(grid.neighbours_8(0, 2)), [(0, 1), (1, 1), (1, 2)]);
+        assert_eq!(sorted(grid.neighbours_8(1, 1)), [(0, 0), (0, 1), (0, 2), (1, 0), (1, 2)]);
+    }
+
+    #[test]
+    fn rows_columns_and_transposing() {
+        let grid = grid();
+        assert_eq!(grid.rows().collect::<Vec<&[char]>>(), [['a', 'b', 'c'], ['d', 'e', 'f']]);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.columns().map(|c| c.collect::<String>()).collect::<Vec<String>>(), ["ad", "be", "cf"]);
+        let transposed = grid.transposed();
+        assert_eq!(transposed.to_string(), "ad\nbe\ncf");
+        assert_eq!(transposed.transposed(), grid);
+        assert_eq!(grid.find(&'e'), Some((1, 1)));
+        assert_eq!(sorted(grid.positions()).len(), 6);
+    }
+
+    #[test]
+    fn wrapping_repeats_the_grid() {
+        let grid = grid();
+        assert_eq!(grid.get_wrapping(0, 0), Some(&'a'));
+        assert_eq!(grid.get_wrapping(-1, -1), Some(&'f'));
+        assert_eq!(grid.get_wrapping(2, 4), Some(&'b'));
+        assert_eq!(grid.get_wrapping(-5, -7), Some(&'f'));
+        assert_eq!(Grid::<char>::from_rows(vec![]).unwrap().get_wrapping(0, 0), None);
+        assert_eq!(Grid::<char>::from_rows(vec![vec![]]).unwrap().get_wrapping(3, -2), None);
+    }
+}
@@ -0,0 +1,282 @@
+use std::cmp::{max, min};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use crate::utils::num::Coord;
+use crate::error::Error;
+
+/// The values from `first` to `last`, both included.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub first: T,
+    pub last: T
+}
+
+impl<T: Coord> Interval<T> {
+    pub fn new(first: T, last: T) -> Self {
+        Interval { first, last }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.first > self.last
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.first <= value && value <= self.last
+    }
+
+    /// The number of values in the interval.
+    pub fn len(&self) -> T {
+        self.last - self.first + T::ONE
+    }
+
+    pub fn range(&self) -> RangeInclusive<T> {
+        self.first..=self.last
+    }
+
+    /// Whether at least one value lies between the end of `self` and the start of `other`,
+    /// so that the two cannot be merged.
+    fn is_apart_before(&self, other: &Self) -> bool {
+        self.last < other.first && self.last + T::ONE < other.first
+    }
+}
+
+impl<T> FromStr for Interval<T> where T: FromStr, T::Err: Into<Error> {
+    type Err = Error;
+    /// Parses `first-last`. Errors point at the bound that failed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (first, last) = s.split_once('-')
+            .ok_or_else(|| Error::Parse(format!("Expected first-last: {}", s.trim())))?;
+        let parse = |bound: &str, start: usize| {
+            let offset = start + bound.len() - bound.trim_start().len();
+            bound.trim().parse::<T>().map_err(|err| err.into().in_span(s, offset..offset + bound.trim().len()))
+        };
+        Ok(Interval { first: parse(first, 0)?, last: parse(last, first.len() + 1)? })
+    }
+}
+
+/// A set of values stored as the intervals it covers. The intervals are kept sorted,
+/// and overlapping or adjacent ones are merged, so every value has exactly one interval.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>
+}
+
+impl<T: Coord> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { intervals: vec![] }
+    }
+
+    /// Adds every value of `interval`, merging it with the intervals it overlaps or touches.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        let start = self.intervals.partition_point(|i| i.is_apart_before(&interval));
+        let end = self.intervals.partition_point(|i| !interval.is_apart_before(i));
+        let mut merged = interval;
+        if start < end {
+            merged.first = min(merged.first, self.intervals[start].first);
+            merged.last = max(merged.last, self.intervals[end - 1].last);
+        }
+        self.intervals.splice(start..end, [merged]);
+        self.debug_check();
+    }
+
+    /// Finds the interval that could hold `value` by binary search.
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.intervals.partition_point(|interval| interval.last < value);
+        self.intervals.get(i).is_some_and(|interval| interval.first <= value)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        other.intervals.iter().for_each(|&interval| union.insert(interval));
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (a, b) = (&self.intervals, &other.intervals);
+        let mut intervals = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            let overlap = Interval { first: max(a[i].first, b[j].first), last: min(a[i].last, b[j].last) };
+            if !overlap.is_empty() {
+                intervals.push(overlap);
+            }
+            if a[i].last < b[j].last {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        let intersection = IntervalSet { intervals };
+        intersection.debug_check();
+        intersection
+    }
+
+    /// The values of `self` that are not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let others = &other.intervals;
+        let mut intervals = vec![];
+        let mut j = 0;
+        for interval in &self.intervals {
+            while j < others.len() && others[j].last < interval.first {
+                j += 1;
+            }
+            // The start of what is left of `interval` after the parts of `other` so far.
+            let mut rest = Some(interval.first);
+            for cut in others[j..].iter().take_while(|cut| cut.first <= interval.last) {
+                let Some(first) = rest else { break };
+                if cut.first > first {
+                    intervals.push(Interval { first, last: cut.first - T::ONE });
+                }
+                rest = (cut.last < interval.last).then(|| cut.last + T::ONE);
+            }
+            if let Some(first) = rest {
+                intervals.push(Interval { first, last: interval.last });
+            }
+        }
+        let difference = IntervalSet { intervals };
+        difference.debug_check();
+        difference
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The number of values in the set.
+    pub fn total_len(&self) -> T {
+        self.intervals.iter().fold(T::ZERO, |total, interval| total + interval.len())
+    }
+
+    /// The intervals in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    /// Asserts in debug builds that the intervals are non-empty, sorted and apart from each other.
+    fn debug_check(&self) {
+        debug_assert!(self.intervals.iter().all(|i| !i.is_empty()), "empty interval in {:?}", self.intervals);
+        debug_assert!(self.intervals.windows(2).all(|w| w[0].is_apart_before(&w[1])),
+            "intervals not sorted and apart: {:?}", self.intervals);
+    }
+}
+
+impl<T: Coord> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        iter.into_iter().for_each(|interval| set.insert(interval));
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use super::*;
+
+    /// A xorshift generator, so the properties are checked on the same cases every run.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+    }
+
+    /// How far from 0 and from `u64::MAX` values are drawn, so the model stays small but both ends are covered.
+    const WINDOW: u64 = 40;
+
+    fn windows() -> impl Iterator<Item = u64> {
+        (0..=WINDOW).chain(u64::MAX - WINDOW..=u64::MAX)
+    }
+
+    /// An interval within one window. Sometimes empty, sometimes a single value, often touching the others.
+    fn interval(rng: &mut Rng) -> Interval<u64> {
+        let base = if rng.below(2) == 0 { 0 } else { u64::MAX - WINDOW };
+        let first = base + rng.below(WINDOW + 1);
+        let last = match rng.below(4) {
+            0 => first.saturating_sub(rng.below(3) + 1).max(base),
+            1 => first,
+            _ => first.saturating_add(rng.below(8)).min(base + WINDOW)
+        };
+        Interval::new(first, last)
+    }
+
+    fn random_set(rng: &mut Rng) -> (IntervalSet<u64>, BTreeSet<u64>) {
+        let mut set = IntervalSet::new();
+        let mut model = BTreeSet::new();
+        for _ in 0..rng.below(8) {
+            let interval = interval(rng);
+            set.insert(interval);
+            if !interval.is_empty() {
+                model.extend(interval.range());
+            }
+        }
+        (set, model)
+    }
+
+    fn assert_matches(set: &IntervalSet<u64>, model: &BTreeSet<u64>) {
+        let values = set.iter().flat_map(|i| i.range()).collect::<BTreeSet<u64>>();
+        assert_eq!(&values, model, "{:?}", set);
+        assert!(set.iter().all(|i| !i.is_empty()), "empty interval in {:?}", set);
+        assert!(set.intervals.windows(2).all(|w| w[0].is_apart_before(&w[1])), "not sorted and apart: {:?}", set);
+        assert_eq!(set.total_len(), model.len() as u64, "{:?}", set);
+        assert_eq!(set.is_empty(), model.is_empty());
+        for value in windows() {
+            assert_eq!(set.contains(value), model.contains(&value), "{} in {:?}", value, set);
+        }
+    }
+
+    #[test]
+    fn operations_match_a_set_of_values() {
+        let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+        for _ in 0..2000 {
+            let (a, model_a) = random_set(&mut rng);
+            let (b, model_b) = random_set(&mut rng);
+            assert_matches(&a, &model_a);
+            assert_matches(&a.union(&b), &model_a.union(&model_b).copied().collect());
+            assert_matches(&a.intersection(&b), &model_a.intersection(&model_b).copied().collect());
+            assert_matches(&a.difference(&b), &model_a.difference(&model_b).copied().collect());
+            assert_matches(&b.difference(&a), &model_b.difference(&model_a).copied().collect());
+        }
+    }
+
+    #[test]
+    fn adjacent_intervals_merge() {
+        let set = [Interval::new(5u64, 7), Interval::new(1, 4), Interval::new(9, 9), Interval::new(8, 8)]
+            .into_iter().collect::<IntervalSet<u64>>();
+        assert_eq!(set.iter().copied().collect::<Vec<Interval<u64>>>(), [Interval::new(1, 9)]);
+    }
+
+    #[test]
+    fn empty_intervals_are_ignored() {
+        let mut set = IntervalSet::new();
+        set.insert(Interval::new(3u64, 2));
+        assert!(set.is_empty());
+        assert!(Interval::new(3u64, 2).is_empty());
+        assert!(!Interval::new(3u64, 2).contains(2));
+    }
+
+    #[test]
+    fn bounds_at_the_maximum() {
+        let max = u64::MAX;
+        let mut set = IntervalSet::new();
+        set.insert(Interval::new(max - 1, max));
+        set.insert(Interval::new(max - 3, max - 2));
+        assert_eq!(set.iter().copied().collect::<Vec<Interval<u64>>>(), [Interval::new(max - 3, max)]);
+        assert!(set.contains(max));
+        assert_eq!(set.total_len(), 4);
+        let cut = IntervalSet::from_iter([Interval::new(max, max)]);
+        assert_eq!(set.difference(&cut).iter().copied().collect::<Vec<Interval<u64>>>(), [Interval::new(max - 3, max - 1)]);
+        assert_eq!(set.intersection(&cut), cut);
+    }
+}
@@ -0,0 +1,24 @@
+use std::fmt::Debug;
+use std::ops::{Add, Mul, Sub};
+
+/// The integer types that points, intervals and path costs are made of.
+pub trait Coord: Copy + Ord + Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// `|self - other|`, which also works for unsigned types.
+    fn distance(self, other: Self) -> Self {
+        if self > other { self - other } else { other - self }
+    }
+}
+
+macro_rules! impl_coord {
+    ($($t:ty),*) => {
+        $(impl Coord for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+        })*
+    };
+}
+
+impl_coord!(i32, i64, isize, u32, u64, usize);
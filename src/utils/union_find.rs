@@ -0,0 +1,135 @@
+/// Disjoint sets over the elements `0..len`, for tracking which elements are connected.
+/// Uses path compression and union by size, so operations take nearly constant time.
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    /// The size of the component of each root. Stale for other elements.
+    size: Vec<usize>,
+    components: usize
+}
+
+impl UnionFind {
+    /// Starts with every element in a component of its own.
+    pub fn new(len: usize) -> Self {
+        UnionFind { parent: (0..len).collect(), size: vec![1; len], components: len }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// The representative of the component `element` is in.
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut current = element;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    /// Merges the components of `a` and `b`. Returns false if they already were the same.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (large, small) = if self.size[a] >= self.size[b] { (a, b) } else { (b, a) };
+        self.parent[small] = large;
+        self.size[large] += self.size[small];
+        self.components -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    pub fn component_size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.size[root]
+    }
+
+    /// The sizes of the `k` largest components, largest first.
+    pub fn largest_components(&self, k: usize) -> impl Iterator<Item = usize> + use<> {
+        let mut sizes = (0..self.len())
+            .filter(|&i| self.parent[i] == i)
+            .map(|i| self.size[i])
+            .collect::<Vec<usize>>();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes.into_iter().take(k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two triangles {0, 1, 2} and {3, 4, 5}, a pair {6, 7} and a lone 8.
+    fn graph() -> UnionFind {
+        let mut sets = UnionFind::new(9);
+        for (a, b) in [(0, 1), (1, 2), (3, 4), (4, 5), (5, 3), (6, 7)] {
+            sets.union(a, b);
+        }
+        sets
+    }
+
+    #[test]
+    fn union_reports_whether_it_merged() {
+        let mut sets = UnionFind::new(3);
+        assert!(sets.union(0, 1));
+        assert!(!sets.union(1, 0));
+        assert!(!sets.union(2, 2));
+        assert!(sets.union(2, 0));
+        assert!(!sets.union(1, 2));
+    }
+
+    #[test]
+    fn find_compresses_paths() {
+        let mut sets = UnionFind::new(4);
+        // Builds the chain 3 -> 2 -> 1 -> 0 by hand, which union by size would never produce.
+        sets.parent = vec![0, 0, 1, 2];
+        assert_eq!(sets.find(3), 0);
+        assert_eq!(sets.parent, [0, 0, 0, 0]);
+        assert!(sets.connected(3, 1));
+    }
+
+    #[test]
+    fn components_are_counted_and_sized() {
+        let mut sets = graph();
+        assert_eq!(sets.len(), 9);
+        assert_eq!(sets.component_count(), 4);
+        assert_eq!(sets.component_size(2), 3);
+        assert_eq!(sets.component_size(5), 3);
+        assert_eq!(sets.component_size(7), 2);
+        assert_eq!(sets.component_size(8), 1);
+        assert!(sets.connected(0, 2));
+        assert!(!sets.connected(2, 3));
+
+        sets.union(2, 6);
+        assert_eq!(sets.component_count(), 3);
+        assert_eq!(sets.component_size(7), 5);
+    }
+
+    #[test]
+    fn largest_components_come_first() {
+        let sets = graph();
+        assert_eq!(sets.largest_components(3).collect::<Vec<usize>>(), [3, 3, 2]);
+        assert_eq!(sets.largest_components(10).collect::<Vec<usize>>(), [3, 3, 2, 1]);
+        assert_eq!(UnionFind::new(0).largest_components(2).count(), 0);
+        assert!(UnionFind::new(0).is_empty());
+    }
+}
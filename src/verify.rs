@@ -0,0 +1,204 @@
+﻿use std::fmt::{Display, Formatter};
+use serde::Serialize;
+use crate::answers::Answers;
+use crate::samples::Manifest;
+use crate::suite;
+use crate::suite::{InputKind, Record, Report, Status};
+
+pub enum Verdict {
+    Correct,
+    Wrong { expected: String },
+    Unknown,
+    Failed,
+    TimedOut,
+    /// The input file is missing. A failure if an answer was expected, since nothing was checked then.
+    NoInput { expected: Option<String> }
+}
+
+impl Verdict {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Verdict::Wrong { .. } | Verdict::Failed | Verdict::TimedOut | Verdict::NoInput { expected: Some(_) })
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "ok"),
+            Verdict::Wrong { .. } => write!(f, "WRONG"),
+            Verdict::Unknown => write!(f, "unknown"),
+            Verdict::Failed => write!(f, "error"),
+            Verdict::TimedOut => write!(f, "timed out"),
+            Verdict::NoInput { expected: Some(_) } => write!(f, "MISSING INPUT"),
+            Verdict::NoInput { expected: None } => write!(f, "no input")
+        }
+    }
+}
+
+pub struct Check {
+    pub record: Record,
+    pub verdict: Verdict
+}
+
+/// Checks `record` against the answer known for its day, part and kind of input:
+/// from `answers` for the actual input, and from the day's first sample in `samples` for the sample.
+pub fn check(record: Record, answers: &Answers, samples: &Manifest) -> Check {
+    let expected = match InputKind::of(&record.source) {
+        Some(InputKind::Actual) => answers.expected(record.day, record.part),
+        Some(InputKind::Sample) => samples.find(record.day, None).and_then(|sample| sample.expected(record.part)),
+        None => None
+    };
+    judge(record, expected)
+}
+
+/// Checks `record` against `expected`, if there is an expectation.
+pub fn judge(record: Record, expected: Option<&str>) -> Check {
+    let verdict = match (&record.status, &record.answer, expected) {
+        (Status::NoInput(_), _, expected) => Verdict::NoInput { expected: expected.map(str::to_string) },
+        (Status::Failed(_), _, _) => Verdict::Failed,
+        (Status::TimedOut(_), _, _) => Verdict::TimedOut,
+        (Status::Ok, _, None) => Verdict::Unknown,
+        (Status::Ok, Some(answer), Some(expected)) if answer == expected => Verdict::Correct,
+        (Status::Ok, _, Some(expected)) => Verdict::Wrong { expected: expected.to_string() }
+    };
+    Check { record, verdict }
+}
+
+/// Stores the answers of all checks on the actual input that had no expectation yet. Returns how many were added.
+/// Sample answers are not recorded, they belong in samples.toml next to their input.
+pub fn record_unknown(checks: &[Check], answers: &mut Answers) -> usize {
+    let mut added = 0;
+    for c in checks {
+        if let (Verdict::Unknown, Some(answer), Some(InputKind::Actual)) = (&c.verdict, &c.record.answer, InputKind::of(&c.record.source)) {
+            answers.record(c.record.day, c.record.part, answer);
+            added += 1;
+        }
+    }
+    added
+}
+
+#[derive(Serialize)]
+pub struct CheckReport {
+    #[serde(flatten)]
+    pub report: Report,
+    pub verdict: String,
+    pub expected: Option<String>
+}
+
+impl From<&Check> for CheckReport {
+    fn from(c: &Check) -> Self {
+        let expected = match &c.verdict {
+            Verdict::Correct => c.record.answer.clone(),
+            Verdict::Wrong { expected } | Verdict::NoInput { expected: Some(expected) } => Some(expected.clone()),
+            _ => None
+        };
+        CheckReport { report: Report::from(&c.record), verdict: c.verdict.to_string(), expected }
+    }
+}
+
+pub fn print_checks(checks: &[Check]) {
+    let labelled = checks.iter().map(|c| (c.record.source.to_string(), c)).collect::<Vec<(String, &Check)>>();
+    print_labelled_checks(&labelled);
+}
+
+/// Prints checks with a custom label in the input column.
+pub fn print_labelled_checks(checks: &[(String, &Check)]) {
+    let header = ["Day", "Part", "Input", "Answer", "Expected", "Status"];
+    let rows = checks.iter().map(|(label, c)| {
+        let answer = match &c.record.status {
+            Status::Failed(err) => suite::truncate(&err.to_string(), 40),
+            Status::NoInput(_) | Status::TimedOut(_) => "-".to_string(),
+            Status::Ok => c.record.answer.clone().unwrap_or_default()
+        };
+        let expected = match &c.verdict {
+            Verdict::Correct => answer.clone(),
+            Verdict::Wrong { expected } | Verdict::NoInput { expected: Some(expected) } => expected.clone(),
+            _ => "-".to_string()
+        };
+        [
+            c.record.day.to_string(),
+            c.record.part.to_string(),
+            label.clone(),
+            answer,
+            expected,
+            c.verdict.to_string()
+        ]
+    }).collect::<Vec<[String; 6]>>();
+
+    let count = |f: fn(&Verdict) -> bool| checks.iter().filter(|(_, c)| f(&c.verdict)).count();
+    let summary = format!("{} ok, {} failed, {} unknown",
+        count(|v| matches!(v, Verdict::Correct)),
+        count(Verdict::is_failure),
+        count(|v| matches!(v, Verdict::Unknown)));
+    let footer = ["Total".to_string(), String::new(), String::new(), String::new(), String::new(), summary];
+    suite::print_rows(header, &rows, Some(footer));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use crate::error::Error;
+    use crate::input::InputSource;
+    use crate::solution::Part;
+    use super::*;
+
+    fn record(status: Status, answer: Option<&str>) -> Record {
+        Record {
+            day: 1,
+            part: Part::One,
+            source: InputSource::Actual,
+            answer: answer.map(str::to_string),
+            status,
+            input_hash: None,
+            parse_time: Duration::ZERO,
+            solve_time: Duration::ZERO,
+            peak_memory: None
+        }
+    }
+
+    fn no_input() -> Status {
+        Status::NoInput(Error::Usage("missing".to_string()))
+    }
+
+    #[test]
+    fn missing_input_fails_only_when_an_answer_is_expected() {
+        let check = judge(record(no_input(), None), Some("42"));
+        assert!(check.verdict.is_failure());
+        assert_eq!(check.verdict.to_string(), "MISSING INPUT");
+        assert_eq!(CheckReport::from(&check).expected.as_deref(), Some("42"));
+
+        let check = judge(record(no_input(), None), None);
+        assert!(!check.verdict.is_failure());
+        assert_eq!(check.verdict.to_string(), "no input");
+    }
+
+    #[test]
+    fn sample_answers_come_from_the_manifest() {
+        let samples = toml::from_str::<Manifest>("[[sample]]\nday = 1\npart1 = \"3\"\ninput = \"x\"").unwrap();
+        let mut answers = Answers::default();
+        answers.record(1, Part::One, "7");
+        let sample = Record { source: InputSource::Sample(None), ..record(Status::Ok, Some("3")) };
+        assert!(matches!(check(sample, &answers, &samples).verdict, Verdict::Correct));
+        assert!(matches!(check(record(Status::Ok, Some("7")), &answers, &samples).verdict, Verdict::Correct));
+        let other_part = Record { part: Part::Two, source: InputSource::Sample(None), ..record(Status::Ok, Some("3")) };
+        assert!(matches!(check(other_part, &answers, &samples).verdict, Verdict::Unknown));
+    }
+
+    #[test]
+    fn only_actual_answers_are_recorded() {
+        let checks = [
+            judge(record(Status::Ok, Some("7")), None),
+            judge(Record { source: InputSource::Sample(None), ..record(Status::Ok, Some("3")) }, None)
+        ];
+        let mut answers = Answers::default();
+        assert_eq!(record_unknown(&checks, &mut answers), 1);
+        assert_eq!(answers.expected(1, Part::One), Some("7"));
+    }
+
+    #[test]
+    fn answers_are_compared_with_the_expectation() {
+        assert!(matches!(judge(record(Status::Ok, Some("42")), Some("42")).verdict, Verdict::Correct));
+        assert!(matches!(judge(record(Status::Ok, Some("41")), Some("42")).verdict, Verdict::Wrong { .. }));
+        assert!(matches!(judge(record(Status::Ok, Some("41")), None).verdict, Verdict::Unknown));
+    }
+}